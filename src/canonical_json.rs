@@ -0,0 +1,112 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+// RFC 8785 JSON 规范化（JCS）：对象键按 UTF-16 码元排序、数字按 ECMAScript 规则输出、
+// 字符串只做最少的转义、不含任何空白，保证相同数据得到逐字节相同的结果，可直接用于签名和哈希
+pub fn to_canonical_json(value: &Value) -> Result<String> {
+    let mut output = String::new();
+    write_value(&mut output, value)?;
+    Ok(output)
+}
+
+fn write_value(output: &mut String, value: &Value) -> Result<()> {
+    match value {
+        Value::Null => output.push_str("null"),
+        Value::Bool(b) => output.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => {
+            let f = n.as_f64().ok_or_else(|| anyhow!("无效的数字: {}", n))?;
+            output.push_str(&format_number(f)?);
+        }
+        Value::String(s) => write_string(output, s),
+        Value::Array(arr) => {
+            output.push('[');
+            for (i, item) in arr.iter().enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                write_value(output, item)?;
+            }
+            output.push(']');
+        }
+        Value::Object(obj) => {
+            let mut entries: Vec<_> = obj.iter().collect();
+            entries.sort_by(|a, b| a.0.encode_utf16().cmp(b.0.encode_utf16()));
+
+            output.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                write_string(output, key);
+                output.push(':');
+                write_value(output, item)?;
+            }
+            output.push('}');
+        }
+    }
+    Ok(())
+}
+
+fn write_string(output: &mut String, s: &str) {
+    output.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\u{8}' => output.push_str("\\b"),
+            '\t' => output.push_str("\\t"),
+            '\n' => output.push_str("\\n"),
+            '\u{c}' => output.push_str("\\f"),
+            '\r' => output.push_str("\\r"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+// 按 ECMAScript Number.prototype.toString 的规则输出 IEEE 754 双精度数
+fn format_number(f: f64) -> Result<String> {
+    if !f.is_finite() {
+        return Err(anyhow!("规范化 JSON 不支持 NaN 和 Infinity"));
+    }
+    if f == 0.0 {
+        return Ok("0".to_string());
+    }
+
+    // Rust 的 {:e} 输出最短的可往返数字，形如 "-1.2345e-7"
+    let formatted = format!("{:e}", f.abs());
+    let (mantissa, exponent) = formatted.split_once('e').ok_or_else(|| anyhow!("无效的数字: {}", f))?;
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    let n = exponent.parse::<i32>()? + 1;
+
+    let mut result = String::new();
+    if f < 0.0 {
+        result.push('-');
+    }
+
+    if k <= n && n <= 21 {
+        result.push_str(&digits);
+        result.push_str(&"0".repeat((n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        result.push_str(&digits[..n as usize]);
+        result.push('.');
+        result.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        result.push_str("0.");
+        result.push_str(&"0".repeat((-n) as usize));
+        result.push_str(&digits);
+    } else {
+        result.push_str(&digits[..1]);
+        if k > 1 {
+            result.push('.');
+            result.push_str(&digits[1..]);
+        }
+        result.push('e');
+        result.push(if n > 0 { '+' } else { '-' });
+        result.push_str(&(n - 1).abs().to_string());
+    }
+
+    Ok(result)
+}
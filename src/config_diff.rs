@@ -0,0 +1,93 @@
+use crate::config_path::{format_path, PathSegment};
+use crate::ConfigValue;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+// 一处差异，路径写成 server.tls.port 的形式
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ConfigChange {
+    Added { path: String, value: Value },
+    Removed { path: String, value: Value },
+    Changed { path: String, old: Value, new: Value },
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DiffOptions {
+    // 按文本比较标量，"8080" 与 8080 视为相同；INI、ENV、XML 等格式的值都是字符串，与其他格式比较时需要
+    pub ignore_types: bool,
+}
+
+// 语义比较两个配置：对象按键比较、与键的顺序无关，数组按下标比较；结果按路径排序
+pub fn diff_configs(old: &ConfigValue, new: &ConfigValue, options: &DiffOptions) -> Vec<ConfigChange> {
+    let old: Map<String, Value> = old.value.clone().into_iter().collect();
+    let new: Map<String, Value> = new.value.clone().into_iter().collect();
+    let mut changes = Vec::new();
+    diff_objects(&old, &new, &mut Vec::new(), options, &mut changes);
+    changes
+}
+
+fn diff_values(old: &Value, new: &Value, path: &mut Vec<PathSegment>, options: &DiffOptions, changes: &mut Vec<ConfigChange>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => diff_objects(old, new, path, options, changes),
+        (Value::Array(old), Value::Array(new)) => {
+            for index in 0..old.len().max(new.len()) {
+                path.push(PathSegment::Index(index));
+                match (old.get(index), new.get(index)) {
+                    (Some(old), Some(new)) => diff_values(old, new, path, options, changes),
+                    (Some(old), None) => changes.push(ConfigChange::Removed { path: format_path(path), value: old.clone() }),
+                    (None, Some(new)) => changes.push(ConfigChange::Added { path: format_path(path), value: new.clone() }),
+                    (None, None) => {}
+                }
+                path.pop();
+            }
+        }
+        (old, new) if !scalars_equal(old, new, options) => changes.push(ConfigChange::Changed {
+            path: format_path(path),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+fn diff_objects(
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+    path: &mut Vec<PathSegment>,
+    options: &DiffOptions,
+    changes: &mut Vec<ConfigChange>,
+) {
+    let mut keys: Vec<&String> = old.keys().chain(new.keys().filter(|key| !old.contains_key(*key))).collect();
+    keys.sort();
+
+    for key in keys {
+        path.push(PathSegment::Key(key.clone()));
+        match (old.get(key), new.get(key)) {
+            (Some(old), Some(new)) => diff_values(old, new, path, options, changes),
+            (Some(old), None) => changes.push(ConfigChange::Removed { path: format_path(path), value: old.clone() }),
+            (None, Some(new)) => changes.push(ConfigChange::Added { path: format_path(path), value: new.clone() }),
+            (None, None) => {}
+        }
+        path.pop();
+    }
+}
+
+fn scalars_equal(old: &Value, new: &Value, options: &DiffOptions) -> bool {
+    if old == new {
+        return true;
+    }
+    if !options.ignore_types || old.is_object() || old.is_array() || new.is_object() || new.is_array() {
+        return false;
+    }
+    scalar_text(old) == scalar_text(new)
+}
+
+// 标量的文本形式；null 视为空字符串，与 INI、ENV 中的空值对应
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,175 @@
+use crate::config_path::{format_path, PathSegment};
+use crate::ConfigValue;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+// 两层都是数组时的合并方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArrayMergeStrategy {
+    // 后面的数组整体替换前面的数组
+    #[default]
+    Replace,
+    // 后面的元素追加到前面的数组末尾
+    Append,
+    // 相同下标的元素递归合并，多出的元素追加
+    ByIndex,
+    // 对象元素按指定字段的值配对后递归合并，没有配对的元素追加
+    ByKey,
+}
+
+impl std::str::FromStr for ArrayMergeStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "replace" => Ok(ArrayMergeStrategy::Replace),
+            "append" => Ok(ArrayMergeStrategy::Append),
+            "index" => Ok(ArrayMergeStrategy::ByIndex),
+            "key" => Ok(ArrayMergeStrategy::ByKey),
+            _ => Err(anyhow::anyhow!("无效的数组合并方式: {}，应为 replace、append、index 或 key", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MergeOptions {
+    pub arrays: ArrayMergeStrategy,
+    // ByKey 时用于配对的字段名
+    pub merge_key: String,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        MergeOptions {
+            arrays: ArrayMergeStrategy::Replace,
+            merge_key: "name".to_string(),
+        }
+    }
+}
+
+// 合并结果；provenance 按路径列出每个最终值来自第几层（从 0 开始）
+#[derive(Debug, Clone)]
+pub struct MergedConfig {
+    pub config: ConfigValue,
+    pub provenance: Vec<(String, usize)>,
+}
+
+// 与值的结构对应的来源树：标量和空的对象、数组记录来源层，非空的对象、数组记录各个子元素的来源
+#[derive(Debug, Clone)]
+enum Origin {
+    Leaf(usize),
+    Object(BTreeMap<String, Origin>),
+    Array(Vec<Origin>),
+}
+
+impl Origin {
+    fn of(value: &Value, layer: usize) -> Self {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                Origin::Object(map.iter().map(|(k, v)| (k.clone(), Origin::of(v, layer))).collect())
+            }
+            Value::Array(values) if !values.is_empty() => Origin::Array(values.iter().map(|v| Origin::of(v, layer)).collect()),
+            _ => Origin::Leaf(layer),
+        }
+    }
+}
+
+// 按顺序深度合并各层：对象按键递归合并，数组按策略合并，其余情况后面的值覆盖前面的值
+pub fn merge_configs(layers: &[ConfigValue], options: &MergeOptions) -> MergedConfig {
+    let mut root = Value::Object(Map::new());
+    let mut origin = Origin::Object(BTreeMap::new());
+    for (layer, config) in layers.iter().enumerate() {
+        let value = Value::Object(config.value.clone().into_iter().collect());
+        merge_value(&mut root, &mut origin, &value, layer, options);
+    }
+
+    let mut provenance = Vec::new();
+    collect_provenance(&origin, &mut Vec::new(), &mut provenance);
+    let value = match root {
+        Value::Object(map) => map.into_iter().collect(),
+        _ => unreachable!(),
+    };
+    MergedConfig { config: ConfigValue { value }, provenance }
+}
+
+fn merge_value(base: &mut Value, origin: &mut Origin, new: &Value, layer: usize, options: &MergeOptions) {
+    match (base, new) {
+        (Value::Object(base), Value::Object(new)) => {
+            if new.is_empty() {
+                return;
+            }
+            // 空对象的来源记为 Leaf，合并进子元素后改为按子元素记录
+            if !matches!(origin, Origin::Object(_)) {
+                *origin = Origin::Object(BTreeMap::new());
+            }
+            let Origin::Object(children) = origin else { unreachable!() };
+            for (key, value) in new {
+                match base.get_mut(key) {
+                    Some(existing) => {
+                        let child = children.entry(key.clone()).or_insert(Origin::Leaf(layer));
+                        merge_value(existing, child, value, layer, options);
+                    }
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                        children.insert(key.clone(), Origin::of(value, layer));
+                    }
+                }
+            }
+        }
+        (Value::Array(base), Value::Array(new)) if options.arrays != ArrayMergeStrategy::Replace => {
+            if new.is_empty() {
+                return;
+            }
+            if !matches!(origin, Origin::Array(_)) {
+                *origin = Origin::Array(Vec::new());
+            }
+            let Origin::Array(children) = origin else { unreachable!() };
+            // 只与前面各层的元素配对，同一层中先追加的元素不参与
+            let previous_len = base.len();
+            for (index, value) in new.iter().enumerate() {
+                let target = match options.arrays {
+                    ArrayMergeStrategy::ByIndex if index < previous_len => Some(index),
+                    ArrayMergeStrategy::ByKey => find_by_key(&base[..previous_len], value, &options.merge_key),
+                    _ => None,
+                };
+                match target {
+                    Some(target) => merge_value(&mut base[target], &mut children[target], value, layer, options),
+                    None => {
+                        base.push(value.clone());
+                        children.push(Origin::of(value, layer));
+                    }
+                }
+            }
+        }
+        (base, new) => {
+            *base = new.clone();
+            *origin = Origin::of(new, layer);
+        }
+    }
+}
+
+// 在 base 中查找与 value 的配对字段值相同的对象元素；配对字段必须是标量
+fn find_by_key(base: &[Value], value: &Value, key: &str) -> Option<usize> {
+    let id = value.get(key).filter(|id| !id.is_object() && !id.is_array())?;
+    base.iter().position(|element| element.get(key) == Some(id))
+}
+
+fn collect_provenance(origin: &Origin, path: &mut Vec<PathSegment>, provenance: &mut Vec<(String, usize)>) {
+    match origin {
+        Origin::Leaf(layer) => provenance.push((format_path(path), *layer)),
+        Origin::Object(children) => {
+            for (key, child) in children {
+                path.push(PathSegment::Key(key.clone()));
+                collect_provenance(child, path, provenance);
+                path.pop();
+            }
+        }
+        Origin::Array(children) => {
+            for (index, child) in children.iter().enumerate() {
+                path.push(PathSegment::Index(index));
+                collect_provenance(child, path, provenance);
+                path.pop();
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::ConfigValue;
+use anyhow::{anyhow, bail, Result};
+use serde_json::{Map, Value};
+
+// 配置值中的一段路径：对象的键或数组的下标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+// 把路径写成 server.tls.port、servers[0].host 的形式；含有特殊字符的键加引号，如 labels."app.kubernetes.io/name"
+pub fn format_path(path: &[PathSegment]) -> String {
+    let mut output = String::new();
+    for segment in path {
+        match segment {
+            PathSegment::Key(key) => {
+                if !output.is_empty() {
+                    output.push('.');
+                }
+                if is_bare_key(key) {
+                    output.push_str(key);
+                } else {
+                    output.push_str(&serde_json::to_string(key).unwrap_or_default());
+                }
+            }
+            PathSegment::Index(index) => output.push_str(&format!("[{}]", index)),
+        }
+    }
+    output
+}
+
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+// 设置路径处的值；缺少的中间层按下一段路径创建对象或数组，数组下标等于长度时追加
+pub fn set_path(config: &mut ConfigValue, path: &[PathSegment], value: Value) -> Result<()> {
+    if !matches!(path.first(), Some(PathSegment::Key(_))) {
+        bail!("路径必须以键开头");
+    }
+    with_root(config, |root| set_in(root, path, value)).map_err(|e| anyhow!("无法设置 {}: {}", format_path(path), e))
+}
+
+// 删除路径处的值并返回被删除的值
+pub fn remove_path(config: &mut ConfigValue, path: &[PathSegment]) -> Result<Value> {
+    let (last, parents) = path.split_last().ok_or_else(|| anyhow!("路径不能为空"))?;
+    let removed = with_root(config, |root| {
+        let parent = parents.iter().try_fold(root, |value, segment| match (segment, value) {
+            (PathSegment::Key(key), Value::Object(map)) => map.get_mut(key),
+            (PathSegment::Index(index), Value::Array(values)) => values.get_mut(*index),
+            _ => None,
+        });
+        match (last, parent) {
+            (PathSegment::Key(key), Some(Value::Object(map))) => map.remove(key),
+            (PathSegment::Index(index), Some(Value::Array(values))) if *index < values.len() => Some(values.remove(*index)),
+            _ => None,
+        }
+    });
+    removed.ok_or_else(|| anyhow!("路径不存在: {}", format_path(path)))
+}
+
+// 把顶层的键值对临时转换为一个对象进行修改，修改后放回
+fn with_root<T>(config: &mut ConfigValue, f: impl FnOnce(&mut Value) -> T) -> T {
+    let mut root = Value::Object(std::mem::take(&mut config.value).into_iter().collect());
+    let result = f(&mut root);
+    if let Value::Object(map) = root {
+        config.value = map.into_iter().collect();
+    }
+    result
+}
+
+fn set_in(target: &mut Value, path: &[PathSegment], value: Value) -> Result<()> {
+    let Some((segment, rest)) = path.split_first() else {
+        *target = value;
+        return Ok(());
+    };
+    if target.is_null() {
+        *target = match segment {
+            PathSegment::Key(_) => Value::Object(Map::new()),
+            PathSegment::Index(_) => Value::Array(Vec::new()),
+        };
+    }
+
+    let child = match (segment, target) {
+        (PathSegment::Key(key), Value::Object(map)) => map.entry(key.clone()).or_insert(Value::Null),
+        (PathSegment::Index(index), Value::Array(values)) => {
+            if *index == values.len() {
+                values.push(Value::Null);
+            }
+            let len = values.len();
+            values.get_mut(*index).ok_or_else(|| anyhow!("下标 {} 超出数组长度 {}", index, len))?
+        }
+        (PathSegment::Key(key), _) => bail!("{} 的上一级不是对象", key),
+        (PathSegment::Index(index), _) => bail!("[{}] 的上一级不是数组", index),
+    };
+    set_in(child, rest, value)
+}
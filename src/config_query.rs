@@ -0,0 +1,306 @@
+use crate::config_path::PathSegment;
+use crate::ConfigValue;
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+// 查询表达式，语法：
+// - server.tls.port、.server.tls.port：逐级取键；含特殊字符的键加引号，如 labels."app.kubernetes.io/name" 或 labels["app.kubernetes.io/name"]
+// - servers[0]、servers[-1]：取数组元素，负数从末尾数起
+// - servers[*].host、env.*：取数组的所有元素或对象的所有值
+// - servers[?port > 80].host、servers[?@.tls.enabled]、hosts[?@ == "a"]：按条件过滤数组元素（或对象的值），
+//   @ 表示元素本身，支持 == != < <= > >=，没有比较运算符时要求值存在且不是 null 或 false；
+//   右侧按 JSON 字面量解析，不是合法的 JSON 时视为字符串
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(i64),
+    Wildcard,
+    Filter(Filter),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Filter {
+    operand: Query,
+    comparison: Option<(Comparison, Value)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+pub fn parse_query(expr: &str) -> Result<Query> {
+    let chars: Vec<char> = expr.trim().chars().collect();
+    let mut parser = QueryParser { chars: &chars, pos: 0 };
+    let steps = parser.parse_steps().map_err(|e| anyhow!("无效的查询表达式 {}: {}", expr, e))?;
+    Ok(Query { steps })
+}
+
+impl Query {
+    // 不含通配符和过滤条件的查询至多匹配一个值
+    pub fn is_single(&self) -> bool {
+        self.steps.iter().all(|step| matches!(step, Step::Key(_) | Step::Index(_)))
+    }
+
+    // 只由键和非负下标组成的查询可以作为修改的目标路径
+    pub fn to_path(&self) -> Option<Vec<PathSegment>> {
+        self.steps
+            .iter()
+            .map(|step| match step {
+                Step::Key(key) => Some(PathSegment::Key(key.clone())),
+                Step::Index(index) => usize::try_from(*index).ok().map(PathSegment::Index),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // 返回所有匹配的值，按文档顺序（对象按键名）排列；路径不存在时返回空
+    pub fn select(&self, config: &ConfigValue) -> Vec<Value> {
+        let root = Value::Object(config.value.clone().into_iter().collect());
+        self.select_from(&root).into_iter().cloned().collect()
+    }
+
+    fn select_from<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        let mut current = vec![root];
+        for step in &self.steps {
+            current = current.into_iter().flat_map(|value| apply_step(step, value)).collect();
+        }
+        current
+    }
+}
+
+fn apply_step<'a>(step: &Step, value: &'a Value) -> Vec<&'a Value> {
+    match (step, value) {
+        (Step::Key(key), Value::Object(map)) => map.get(key).into_iter().collect(),
+        (Step::Index(index), Value::Array(values)) => {
+            let index = if *index < 0 { values.len() as i64 + index } else { *index };
+            usize::try_from(index).ok().and_then(|i| values.get(i)).into_iter().collect()
+        }
+        (Step::Wildcard, Value::Object(map)) => map.values().collect(),
+        (Step::Wildcard, Value::Array(values)) => values.iter().collect(),
+        (Step::Filter(filter), Value::Object(map)) => map.values().filter(|v| filter.matches(v)).collect(),
+        (Step::Filter(filter), Value::Array(values)) => values.iter().filter(|v| filter.matches(v)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+impl Filter {
+    fn matches(&self, element: &Value) -> bool {
+        let operands = self.operand.select_from(element);
+        match &self.comparison {
+            None => operands.iter().any(|v| !matches!(v, Value::Null | Value::Bool(false))),
+            Some((comparison, expected)) => operands.iter().any(|v| compare(v, *comparison, expected)),
+        }
+    }
+}
+
+fn compare(actual: &Value, comparison: Comparison, expected: &Value) -> bool {
+    let ordering = match (actual, expected) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64().zip(b.as_f64()).and_then(|(a, b)| a.partial_cmp(&b)),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (a, b) if a == b => Some(std::cmp::Ordering::Equal),
+        _ => None,
+    };
+    match comparison {
+        Comparison::Eq => ordering == Some(std::cmp::Ordering::Equal),
+        Comparison::Ne => ordering != Some(std::cmp::Ordering::Equal),
+        Comparison::Lt => ordering.is_some_and(|o| o.is_lt()),
+        Comparison::Le => ordering.is_some_and(|o| o.is_le()),
+        Comparison::Gt => ordering.is_some_and(|o| o.is_gt()),
+        Comparison::Ge => ordering.is_some_and(|o| o.is_ge()),
+    }
+}
+
+struct QueryParser<'a> {
+    chars: &'a [char],
+    pos: usize,
+}
+
+impl QueryParser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => Err(anyhow!("第 {} 个字符处应为 {}，实际为 {}", self.pos + 1, expected, c)),
+            None => Err(anyhow!("缺少 {}", expected)),
+        }
+    }
+
+    // 解析到表达式结尾；开头的 . 可以省略，单独的 . 表示根
+    fn parse_steps(&mut self) -> Result<Vec<Step>> {
+        let mut steps = Vec::new();
+        if self.peek() == Some('.') {
+            self.pos += 1;
+        }
+        if self.peek().is_some_and(|c| c != '[') {
+            steps.push(self.parse_segment()?);
+        }
+        while let Some(c) = self.peek() {
+            match c {
+                '.' => {
+                    self.pos += 1;
+                    steps.push(self.parse_segment()?);
+                }
+                '[' => {
+                    self.pos += 1;
+                    steps.push(self.parse_bracket()?);
+                }
+                c => return Err(anyhow!("第 {} 个字符处出现意外的 {}", self.pos + 1, c)),
+            }
+        }
+        Ok(steps)
+    }
+
+    fn parse_segment(&mut self) -> Result<Step> {
+        match self.peek() {
+            Some('*') => {
+                self.pos += 1;
+                Ok(Step::Wildcard)
+            }
+            Some('"') => Ok(Step::Key(self.parse_quoted()?)),
+            _ => {
+                let start = self.pos;
+                while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+                    self.pos += 1;
+                }
+                if start == self.pos {
+                    return Err(anyhow!("第 {} 个字符处缺少键名", self.pos + 1));
+                }
+                Ok(Step::Key(self.chars[start..self.pos].iter().collect()))
+            }
+        }
+    }
+
+    fn parse_bracket(&mut self) -> Result<Step> {
+        self.skip_whitespace();
+        let step = match self.peek() {
+            Some('*') => {
+                self.pos += 1;
+                Step::Wildcard
+            }
+            Some('?') => {
+                self.pos += 1;
+                Step::Filter(self.parse_filter()?)
+            }
+            Some('"') => Step::Key(self.parse_quoted()?),
+            _ => {
+                let start = self.pos;
+                while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '-') {
+                    self.pos += 1;
+                }
+                let text: String = self.chars[start..self.pos].iter().collect();
+                Step::Index(text.parse().map_err(|_| anyhow!("第 {} 个字符处应为下标、*、?条件或带引号的键", start + 1))?)
+            }
+        };
+        self.expect(']')?;
+        Ok(step)
+    }
+
+    // 引号内按 JSON 字符串转义解析
+    fn parse_quoted(&mut self) -> Result<String> {
+        let start = self.pos;
+        self.pos += 1;
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '\\' => self.pos += 1,
+                '"' => {
+                    let text: String = self.chars[start..self.pos].iter().collect();
+                    return serde_json::from_str(&text).map_err(|e| anyhow!("无效的带引号的键 {}: {}", text, e));
+                }
+                _ => {}
+            }
+        }
+        Err(anyhow!("未闭合的引号"))
+    }
+
+    // 条件取到与 [ 配对的 ] 为止，引号内的 ] 不算
+    fn parse_filter(&mut self) -> Result<Filter> {
+        let start = self.pos;
+        let mut depth = 0;
+        let mut quoted = false;
+        while let Some(c) = self.peek() {
+            match c {
+                '\\' if quoted => self.pos += 1,
+                '"' => quoted = !quoted,
+                '[' if !quoted => depth += 1,
+                ']' if !quoted && depth == 0 => break,
+                ']' if !quoted => depth -= 1,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos.min(self.chars.len())].iter().collect();
+        let text = text.trim();
+
+        let (operand, comparison) = match find_operator(text) {
+            Some((position, operator, comparison)) => {
+                let literal = text[position + operator.len()..].trim();
+                let expected = serde_json::from_str(literal).unwrap_or_else(|_| Value::String(literal.to_string()));
+                (text[..position].trim(), Some((comparison, expected)))
+            }
+            None => (text, None),
+        };
+        if operand.is_empty() {
+            return Err(anyhow!("过滤条件缺少左侧的路径"));
+        }
+        let operand = parse_query(operand.strip_prefix('@').unwrap_or(operand))?;
+        Ok(Filter { operand, comparison })
+    }
+}
+
+// 在引号外查找比较运算符，两个字符的运算符优先
+fn find_operator(text: &str) -> Option<(usize, &'static str, Comparison)> {
+    const OPERATORS: [(&str, Comparison); 6] = [
+        ("==", Comparison::Eq),
+        ("!=", Comparison::Ne),
+        ("<=", Comparison::Le),
+        (">=", Comparison::Ge),
+        ("<", Comparison::Lt),
+        (">", Comparison::Gt),
+    ];
+    let mut quoted = false;
+    let mut escaped = false;
+    for (position, c) in text.char_indices() {
+        if quoted {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => quoted = false,
+                _ => {}
+            }
+            continue;
+        }
+        if c == '"' {
+            quoted = true;
+            continue;
+        }
+        if let Some((operator, comparison)) = OPERATORS.iter().find(|(operator, _)| text[position..].starts_with(operator)) {
+            return Some((position, operator, *comparison));
+        }
+    }
+    None
+}
@@ -1,8 +1,14 @@
-use crate::{ConfigConverter, ConfigValue};
+use crate::{gitconfig_parser, hcl_parser, json5_parser, kdl_parser, properties_parser, ron_parser, systemd_parser, ConfigConverter, ConfigValue};
 use anyhow::{anyhow, Result};
-use configparser::ini::Ini;
+use base64::Engine;
+use configparser::ini::{Ini, IniDefault};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+// 顶层 HashMap 的遍历顺序不固定，输出前按键名排序，保证同一输入总是得到相同的输出
+fn sorted_root(config: &ConfigValue) -> BTreeMap<&String, &Value> {
+    config.value.iter().collect()
+}
 
 impl ConfigConverter for crate::IniConverter {
     fn parse(&self, content: &str) -> Result<ConfigValue> {
@@ -27,7 +33,7 @@ impl ConfigConverter for crate::IniConverter {
     fn format(&self, config: &ConfigValue) -> Result<String> {
         let mut ini = Ini::new();
         
-        for (section, value) in &config.value {
+        for (section, value) in sorted_root(config) {
             if let Value::Object(props) = value {
                 for (key, val) in props {
                     if let Value::String(s) = val {
@@ -48,10 +54,127 @@ impl ConfigConverter for crate::JsonConverter {
     }
 
     fn format(&self, config: &ConfigValue) -> Result<String> {
-        Ok(serde_json::to_string_pretty(&config.value)?)
+        let options = &self.options;
+        if options.canonical {
+            let root: serde_json::Map<String, Value> = config.value.clone().into_iter().collect();
+            return crate::to_canonical_json(&Value::Object(root));
+        }
+
+        let mut buf = Vec::new();
+
+        write_json(&mut buf, &sorted_root(config), options)?;
+
+        let mut output = String::from_utf8(buf)?;
+        if options.ascii {
+            output = escape_non_ascii(&output);
+        }
+        if options.trailing_newline {
+            output.push('\n');
+        }
+        Ok(output)
+    }
+}
+
+fn write_json<T: serde::Serialize>(buf: &mut Vec<u8>, value: &T, options: &crate::JsonOptions) -> Result<()> {
+    if options.compact {
+        serde_json::to_writer(buf, value)?;
+    } else {
+        let indent = match options.indent {
+            crate::JsonIndent::Spaces(n) => " ".repeat(n),
+            crate::JsonIndent::Tab => "\t".to_string(),
+        };
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+        let mut serializer = serde_json::Serializer::with_formatter(buf, formatter);
+        value.serialize(&mut serializer)?;
+    }
+    Ok(())
+}
+
+// JSON 输出中非 ASCII 字符只会出现在字符串内，直接逐字符转义即可
+fn escape_non_ascii(json: &str) -> String {
+    let mut output = String::with_capacity(json.len());
+    for c in json.chars() {
+        if c.is_ascii() {
+            output.push(c);
+        } else {
+            let mut units = [0u16; 2];
+            for unit in c.encode_utf16(&mut units) {
+                output.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    }
+    output
+}
+
+impl ConfigConverter for crate::Json5Converter {
+    fn parse(&self, content: &str) -> Result<ConfigValue> {
+        Ok(self.parse_with_comments(content)?.0)
+    }
+
+    fn format(&self, config: &ConfigValue) -> Result<String> {
+        self.format_with_comments(config, &HashMap::new())
     }
 }
 
+impl crate::Json5Converter {
+    // 解析时一并返回注释，键为注释所属的路径（如 "compilerOptions.strict"、"plugins.0"）
+    pub fn parse_with_comments(&self, content: &str) -> Result<(ConfigValue, HashMap<String, String>)> {
+        let (value, comments) = json5_parser::parse_json5(content)?;
+        Ok((ConfigValue { value }, comments))
+    }
+
+    // 输出带 // 注释的 JSON，JSONC 和 JSON5 都能读取
+    pub fn format_with_comments(&self, config: &ConfigValue, comments: &HashMap<String, String>) -> Result<String> {
+        let root: serde_json::Map<String, Value> = config.value.clone().into_iter().collect();
+        let mut output = String::new();
+        format_json5_value(&mut output, &Value::Object(root), comments, "", 0)?;
+        output.push('\n');
+        Ok(output)
+    }
+}
+
+fn format_json5_value(
+    output: &mut String,
+    value: &Value,
+    comments: &HashMap<String, String>,
+    path: &str,
+    indent: usize,
+) -> Result<()> {
+    let indent_str = "  ".repeat(indent + 1);
+    let children: Vec<(String, &Value)> = match value {
+        Value::Object(obj) if !obj.is_empty() => obj.iter().map(|(k, v)| (k.clone(), v)).collect(),
+        Value::Array(arr) if !arr.is_empty() => arr.iter().enumerate().map(|(i, v)| (i.to_string(), v)).collect(),
+        _ => {
+            output.push_str(&serde_json::to_string(value)?);
+            return Ok(());
+        }
+    };
+
+    let is_object = value.is_object();
+    output.push(if is_object { '{' } else { '[' });
+    output.push('\n');
+    for (i, (key, child)) in children.iter().enumerate() {
+        let child_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+        if let Some(comment) = comments.get(&child_path) {
+            for line in comment.lines() {
+                output.push_str(&format!("{}// {}\n", indent_str, line));
+            }
+        }
+        output.push_str(&indent_str);
+        if is_object {
+            output.push_str(&format!("{}: ", serde_json::to_string(key)?));
+        }
+        format_json5_value(output, child, comments, &child_path, indent + 1)?;
+        if i + 1 < children.len() {
+            output.push(',');
+        }
+        output.push('\n');
+    }
+    output.push_str(&"  ".repeat(indent));
+    output.push(if is_object { '}' } else { ']' });
+    Ok(())
+}
+
 impl ConfigConverter for crate::YamlConverter {
     fn parse(&self, content: &str) -> Result<ConfigValue> {
         let value: HashMap<String, Value> = serde_yaml::from_str(content)?;
@@ -59,7 +182,7 @@ impl ConfigConverter for crate::YamlConverter {
     }
 
     fn format(&self, config: &ConfigValue) -> Result<String> {
-        Ok(serde_yaml::to_string(&config.value)?)
+        Ok(serde_yaml::to_string(&sorted_root(config))?)
     }
 }
 
@@ -70,7 +193,8 @@ impl ConfigConverter for crate::TomlConverter {
     }
 
     fn format(&self, config: &ConfigValue) -> Result<String> {
-        Ok(toml::to_string_pretty(&config.value)?)
+        let doc = crate::toml_writer::to_document(&config.value, &self.options)?;
+        Ok(doc.to_string())
     }
 }
 
@@ -95,7 +219,7 @@ impl ConfigConverter for crate::EnvConverter {
     fn format(&self, config: &ConfigValue) -> Result<String> {
         let mut output = String::new();
         
-        for (key, value) in &config.value {
+        for (key, value) in sorted_root(config) {
             if let Value::String(s) = value {
                 output.push_str(&format!("{}={}\n", key, s));
             }
@@ -114,6 +238,1165 @@ impl ConfigConverter for crate::XmlConverter {
     }
 
     fn format(&self, config: &ConfigValue) -> Result<String> {
-        Ok(quick_xml::se::to_string(&config.value)?)
+        Ok(quick_xml::se::to_string(&sorted_root(config))?)
+    }
+}
+
+impl ConfigConverter for crate::PropertiesConverter {
+    fn parse(&self, content: &str) -> Result<ConfigValue> {
+        let value = properties_parser::parse_properties(content)?;
+        Ok(ConfigValue { value })
+    }
+
+    fn format(&self, config: &ConfigValue) -> Result<String> {
+        let mut entries = Vec::new();
+        for (key, value) in &config.value {
+            properties_parser::flatten(key, value, &mut entries);
+        }
+        entries.sort();
+
+        let mut output = String::new();
+        for (key, value) in entries {
+            output.push_str(&format!(
+                "{}={}\n",
+                properties_parser::escape(&key, true, self.options.unicode_escape),
+                properties_parser::escape(&value, false, self.options.unicode_escape)
+            ));
+        }
+        Ok(output)
+    }
+
+    // properties 文件可能是 ISO-8859-1 编码
+    fn parse_bytes(&self, bytes: &[u8]) -> Result<ConfigValue> {
+        self.parse(&properties_parser::decode(bytes))
+    }
+}
+
+impl crate::PropertiesConverter {
+    // 读取原始字节：合法 UTF-8 按 UTF-8 处理，否则按 ISO-8859-1 解码
+    pub fn decode(bytes: &[u8]) -> String {
+        properties_parser::decode(bytes)
+    }
+}
+
+impl ConfigConverter for crate::PlistConverter {
+    fn parse(&self, content: &str) -> Result<ConfigValue> {
+        self.parse_bytes(content.as_bytes())
+    }
+
+    fn format(&self, config: &ConfigValue) -> Result<String> {
+        Ok(String::from_utf8(to_plist_xml(config)?)?)
     }
-} 
\ No newline at end of file
+
+    // 自动识别 XML 和二进制 bplist00
+    fn parse_bytes(&self, bytes: &[u8]) -> Result<ConfigValue> {
+        let plist = plist::Value::from_reader(std::io::Cursor::new(bytes)).map_err(|e| anyhow!("plist 解析错误: {}", e))?;
+        match from_plist(plist)? {
+            Value::Object(map) => Ok(ConfigValue { value: map.into_iter().collect() }),
+            _ => Err(anyhow!("plist 根节点必须是字典")),
+        }
+    }
+
+    fn format_bytes(&self, config: &ConfigValue) -> Result<Vec<u8>> {
+        if !self.options.binary {
+            return to_plist_xml(config);
+        }
+        let mut buf = Vec::new();
+        to_plist_root(config)?.to_writer_binary(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+// <date> 和 <data> 在 JSON 中没有对应类型，分别映射为 {"$date": "RFC 3339 时间"} 和 {"$data": "base64"}
+const PLIST_DATE_KEY: &str = "$date";
+const PLIST_DATA_KEY: &str = "$data";
+
+fn from_plist(value: plist::Value) -> Result<Value> {
+    Ok(match value {
+        plist::Value::Array(arr) => Value::Array(arr.into_iter().map(from_plist).collect::<Result<_>>()?),
+        plist::Value::Dictionary(dict) => {
+            let mut map = serde_json::Map::new();
+            for (k, v) in dict {
+                map.insert(k, from_plist(v)?);
+            }
+            Value::Object(map)
+        }
+        plist::Value::Boolean(b) => Value::Bool(b),
+        plist::Value::Data(data) => {
+            let encoded = base64::engine::general_purpose::STANDARD.encode(data);
+            serde_json::json!({ PLIST_DATA_KEY: encoded })
+        }
+        plist::Value::Date(date) => serde_json::json!({ PLIST_DATE_KEY: date.to_xml_format() }),
+        plist::Value::Real(f) => serde_json::Number::from_f64(f)
+            .map(Value::Number)
+            .ok_or_else(|| anyhow!("无效的浮点数: {}", f))?,
+        plist::Value::Integer(i) => match (i.as_signed(), i.as_unsigned()) {
+            (Some(n), _) => Value::from(n),
+            (None, Some(n)) => Value::from(n),
+            _ => return Err(anyhow!("无效的整数: {}", i)),
+        },
+        plist::Value::String(s) => Value::String(s),
+        plist::Value::Uid(uid) => Value::from(uid.get()),
+        _ => return Err(anyhow!("不支持的 plist 值类型")),
+    })
+}
+
+fn to_plist_xml(config: &ConfigValue) -> Result<Vec<u8>> {
+    for (key, value) in &config.value {
+        check_xml_text(key)?;
+        check_plist_xml_value(value)?;
+    }
+    let mut buf = Vec::new();
+    to_plist_root(config)?.to_writer_xml(&mut buf)?;
+    Ok(buf)
+}
+
+// XML 1.0 不允许 \t、\n、\r 以外的控制字符，原样写出的 plist 会被其他读取器拒绝；二进制 plist 没有这个限制
+fn check_plist_xml_value(value: &Value) -> Result<()> {
+    match value {
+        Value::String(s) => check_xml_text(s),
+        Value::Array(arr) => arr.iter().try_for_each(check_plist_xml_value),
+        Value::Object(obj) => obj.iter().try_for_each(|(k, v)| {
+            check_xml_text(k)?;
+            check_plist_xml_value(v)
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn check_xml_text(s: &str) -> Result<()> {
+    let invalid = s
+        .chars()
+        .find(|c| matches!(c, '\0'..='\u{8}' | '\u{b}' | '\u{c}' | '\u{e}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}'));
+    match invalid {
+        Some(c) => Err(anyhow!("XML plist 不能包含字符 U+{:04X}: {:?}，可以改用 --plist-binary 输出二进制 plist", c as u32, s)),
+        None => Ok(()),
+    }
+}
+
+fn to_plist_root(config: &ConfigValue) -> Result<plist::Value> {
+    let root: serde_json::Map<String, Value> = config.value.clone().into_iter().collect();
+    to_plist(&Value::Object(root))
+}
+
+fn to_plist(value: &Value) -> Result<plist::Value> {
+    Ok(match value {
+        Value::Null => return Err(anyhow!("plist 不支持 null 值")),
+        Value::Bool(b) => plist::Value::Boolean(*b),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                plist::Value::Integer(i.into())
+            } else if let Some(u) = n.as_u64() {
+                plist::Value::Integer(u.into())
+            } else {
+                plist::Value::Real(n.as_f64().ok_or_else(|| anyhow!("无效的数字: {}", n))?)
+            }
+        }
+        Value::String(s) => plist::Value::String(s.clone()),
+        Value::Array(arr) => plist::Value::Array(arr.iter().map(to_plist).collect::<Result<_>>()?),
+        Value::Object(obj) => {
+            if obj.len() == 1 {
+                if let Some(Value::String(s)) = obj.get(PLIST_DATE_KEY) {
+                    let date = plist::Date::from_xml_format(s).map_err(|e| anyhow!("无效的日期 {}: {}", s, e))?;
+                    return Ok(plist::Value::Date(date));
+                }
+                if let Some(Value::String(s)) = obj.get(PLIST_DATA_KEY) {
+                    let data = base64::engine::general_purpose::STANDARD
+                        .decode(s)
+                        .map_err(|e| anyhow!("无效的 base64 数据: {}", e))?;
+                    return Ok(plist::Value::Data(data));
+                }
+            }
+            let mut dict = plist::Dictionary::new();
+            for (k, v) in obj {
+                dict.insert(k.clone(), to_plist(v)?);
+            }
+            plist::Value::Dictionary(dict)
+        }
+    })
+}
+
+impl ConfigConverter for crate::HclConverter {
+    fn parse(&self, content: &str) -> Result<ConfigValue> {
+        let value = hcl_parser::parse_hcl(content)?;
+        Ok(ConfigValue { value })
+    }
+
+    fn format(&self, config: &ConfigValue) -> Result<String> {
+        let root: serde_json::Map<String, Value> = config.value.clone().into_iter().collect();
+        let mut output = String::new();
+        format_hcl_body(&mut output, &root, 0, self.options.blocks)?;
+        Ok(output)
+    }
+}
+
+// Terraform 块关键字的标签层数和块体内能否再有块，如 resource "type" "name" {}；locals、variable 等块体内只有属性
+fn hcl_block_keyword(name: &str, top_level: bool) -> Option<(usize, bool)> {
+    match (name, top_level) {
+        ("resource" | "data", true) => Some((2, true)),
+        ("provider", true) => Some((1, true)),
+        ("variable" | "output" | "module", true) => Some((1, false)),
+        ("terraform", true) => Some((0, true)),
+        ("locals", true) => Some((0, false)),
+        ("backend" | "provisioner" | "dynamic", false) => Some((1, true)),
+        ("lifecycle" | "connection" | "required_providers", false) => Some((0, false)),
+        _ => None,
+    }
+}
+
+fn is_hcl_identifier(key: &str) -> bool {
+    key.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+// 按 terraform fmt 的风格输出：属性在前，块在后，块之间空一行
+fn format_hcl_body(output: &mut String, body: &serde_json::Map<String, Value>, indent: usize, blocks: bool) -> Result<()> {
+    let indent_str = "  ".repeat(indent);
+    let mut attributes = Vec::new();
+    let mut block_list = Vec::new();
+
+    for (key, value) in body {
+        // 块关键字按约定的标签层数展开，其余只有对象数组写成重复的块，普通对象仍是 key = { ... } 属性
+        let keyword = hcl_block_keyword(key, indent == 0);
+        let found = match keyword {
+            Some((labels, _)) => collect_hcl_blocks(value, labels),
+            None if value.is_array() => collect_hcl_blocks(value, 0),
+            None => None,
+        };
+        let nested = keyword.is_none_or(|(_, nested)| nested);
+        match (blocks, found) {
+            (true, Some(found)) => block_list.push((key, found, nested)),
+            _ => {
+                if !is_hcl_identifier(key) {
+                    return Err(anyhow!("无效的 HCL 属性名: {}", key));
+                }
+                attributes.push((key.clone(), format_hcl_expression(value, indent)?));
+            }
+        }
+    }
+
+    push_hcl_attributes(output, &indent_str, &attributes);
+
+    for (i, (key, found, nested)) in block_list.into_iter().enumerate() {
+        for (j, (labels, block)) in found.into_iter().enumerate() {
+            if i > 0 || j > 0 || !attributes.is_empty() {
+                output.push('\n');
+            }
+            output.push_str(&indent_str);
+            output.push_str(key);
+            for label in labels {
+                output.push_str(&format!(" {}", serde_json::to_string(&label)?));
+            }
+            output.push_str(" {\n");
+            format_hcl_body(output, block, indent + 1, nested)?;
+            output.push_str(&format!("{}}}\n", indent_str));
+        }
+    }
+
+    Ok(())
+}
+
+// 连续的单行属性按 = 对齐，多行属性会打断对齐分组
+fn push_hcl_attributes(output: &mut String, indent_str: &str, attributes: &[(String, String)]) {
+    let mut group_start = 0;
+    while group_start < attributes.len() {
+        let mut group_end = group_start;
+        while group_end < attributes.len() && !attributes[group_end].1.contains('\n') {
+            group_end += 1;
+        }
+        let group_end = group_end.max(group_start + 1);
+        let width = attributes[group_start..group_end].iter().map(|(k, _)| k.chars().count()).max().unwrap_or(0);
+        for (key, value) in &attributes[group_start..group_end] {
+            output.push_str(&format!("{}{:width$} = {}\n", indent_str, key, value, width = width));
+        }
+        group_start = group_end;
+    }
+}
+
+// 块的标签和块体
+type HclBlock<'a> = (Vec<String>, &'a serde_json::Map<String, Value>);
+
+// 按给定的标签层数展开为块列表，结构不符合块的形状时返回 None
+fn collect_hcl_blocks(value: &Value, labels: usize) -> Option<Vec<HclBlock<'_>>> {
+    match value {
+        Value::Object(obj) if labels > 0 => {
+            let mut found = Vec::new();
+            for (label, child) in obj {
+                for (mut rest, block) in collect_hcl_blocks(child, labels - 1)? {
+                    rest.insert(0, label.clone());
+                    found.push((rest, block));
+                }
+            }
+            Some(found)
+        }
+        // 键都是合法标识符的对象才能写成块，否则作为对象表达式输出
+        Value::Object(obj) if obj.keys().all(|k| is_hcl_identifier(k)) => Some(vec![(Vec::new(), obj)]),
+        Value::Array(arr) if !arr.is_empty() && arr.iter().all(Value::is_object) => {
+            arr.iter().map(|v| collect_hcl_blocks(v, 0).map(|mut b| b.remove(0))).collect()
+        }
+        _ => None,
+    }
+}
+
+fn format_hcl_expression(value: &Value, indent: usize) -> Result<String> {
+    let indent_str = "  ".repeat(indent);
+    Ok(match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => format_hcl_string(s, &indent_str),
+        Value::Array(arr) if arr.is_empty() => "[]".to_string(),
+        Value::Array(arr) => {
+            let items = arr
+                .iter()
+                .map(|v| format_hcl_expression(v, indent + 1))
+                .collect::<Result<Vec<_>>>()?;
+            let single_line = format!("[{}]", items.join(", "));
+            if !single_line.contains('\n') && single_line.len() + indent_str.len() <= 80 {
+                single_line
+            } else {
+                let mut result = String::from("[\n");
+                for item in items {
+                    result.push_str(&format!("{}  {},\n", indent_str, item));
+                }
+                result.push_str(&format!("{}]", indent_str));
+                result
+            }
+        }
+        Value::Object(obj) if obj.is_empty() => "{}".to_string(),
+        Value::Object(obj) => {
+            let entries = obj
+                .iter()
+                .map(|(k, v)| {
+                    let key = if is_hcl_identifier(k) { k.clone() } else { serde_json::to_string(k)? };
+                    Ok((key, format_hcl_expression(v, indent + 1)?))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let mut result = String::from("{\n");
+            push_hcl_attributes(&mut result, &format!("{}  ", indent_str), &entries);
+            result.push_str(&format!("{}}}", indent_str));
+            result
+        }
+    })
+}
+
+// 整个字符串就是一个 ${ } 插值时直接输出表达式；以换行结尾的多行字符串用 heredoc
+fn format_hcl_string(s: &str, indent_str: &str) -> String {
+    if let Some(inner) = s.strip_prefix("${").and_then(|r| r.strip_suffix('}')) {
+        if !inner.contains("${") && !inner.contains('}') && !inner.trim().is_empty() {
+            return inner.to_string();
+        }
+    }
+
+    // heredoc 总以换行结尾，<<- 还会去掉共同的缩进，只有以换行结尾且各行没有前导空白的字符串才能原样还原
+    let body = s.strip_suffix('\n').unwrap_or(s);
+    let heredoc = s.ends_with('\n')
+        && !s.contains('\r')
+        && body.split('\n').all(|l| !l.starts_with(char::is_whitespace) && l.trim() != "EOT");
+    if heredoc {
+        return format!("<<-EOT\n{}\n{}EOT", body
+            .split('\n')
+            .map(|l| if l.is_empty() { String::new() } else { format!("{}  {}", indent_str, l) })
+            .collect::<Vec<_>>()
+            .join("\n"), indent_str);
+    }
+
+    let mut result = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+impl ConfigConverter for crate::RonConverter {
+    fn parse(&self, content: &str) -> Result<ConfigValue> {
+        let value = ron_parser::parse_ron(content)?;
+        Ok(ConfigValue { value })
+    }
+
+    fn format(&self, config: &ConfigValue) -> Result<String> {
+        // 键都是标识符的对象写成匿名结构体，其余写成字典；null 写成 None，枚举按外部标签还原，
+        // 并启用 implicit_some 使普通值可以直接反序列化为 Option 字段
+        let root: serde_json::Map<String, Value> = config.value.clone().into_iter().collect();
+        let mut output = String::from("#![enable(implicit_some)]\n");
+        format_ron_value(&mut output, &Value::Object(root), 0)?;
+        output.push('\n');
+        Ok(output)
+    }
+}
+
+fn is_ron_identifier(key: &str) -> bool {
+    key.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn format_ron_value(output: &mut String, value: &Value, indent: usize) -> Result<()> {
+    let indent_str = "    ".repeat(indent + 1);
+    match value {
+        Value::Null => output.push_str("None"),
+        Value::Bool(b) => output.push_str(&b.to_string()),
+        Value::Number(n) => {
+            let text = n.to_string();
+            // RON 中浮点数必须带小数点或指数
+            if n.is_f64() && !text.contains(['.', 'e', 'E']) {
+                output.push_str(&format!("{}.0", text));
+            } else {
+                output.push_str(&text);
+            }
+        }
+        Value::String(s) => write_ron_string(output, s),
+        Value::Array(arr) if arr.is_empty() => output.push_str("[]"),
+        Value::Array(arr) => {
+            output.push_str("[\n");
+            for item in arr {
+                output.push_str(&indent_str);
+                format_ron_value(output, item, indent + 1)?;
+                output.push_str(",\n");
+            }
+            output.push_str(&"    ".repeat(indent));
+            output.push(']');
+        }
+        Value::Object(obj) if obj.is_empty() => output.push_str("{}"),
+        // 首字母大写的单键对象按枚举变体输出，如 {"Rgb": 255} 写成 Rgb(255)；
+        // 内容是多个元素的数组时各元素作为元组字段，{"Rgb": [255, 0, 0]} 写成 Rgb(255, 0, 0)
+        Value::Object(obj) if obj.len() == 1 && obj.keys().all(|k| is_ron_identifier(k) && k.starts_with(char::is_uppercase)) => {
+            for (variant, content) in obj {
+                output.push_str(variant);
+                output.push('(');
+                match content {
+                    Value::Array(fields) if fields.len() >= 2 => {
+                        for (i, field) in fields.iter().enumerate() {
+                            if i > 0 {
+                                output.push_str(", ");
+                            }
+                            format_ron_value(output, field, indent)?;
+                        }
+                    }
+                    content => format_ron_value(output, content, indent)?,
+                }
+                output.push(')');
+            }
+        }
+        Value::Object(obj) => {
+            let is_struct = obj.keys().all(|k| is_ron_identifier(k));
+            output.push_str(if is_struct { "(\n" } else { "{\n" });
+            for (key, item) in obj {
+                output.push_str(&indent_str);
+                if is_struct {
+                    output.push_str(key);
+                } else {
+                    write_ron_string(output, key);
+                }
+                output.push_str(": ");
+                format_ron_value(output, item, indent + 1)?;
+                output.push_str(",\n");
+            }
+            output.push_str(&"    ".repeat(indent));
+            output.push(if is_struct { ')' } else { '}' });
+        }
+    }
+    Ok(())
+}
+
+// RON 的 Unicode 转义写成 \u{1}，不接受 JSON 的 \u0001、\b、\f
+fn write_ron_string(output: &mut String, s: &str) {
+    output.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\t' => output.push_str("\\t"),
+            '\r' => output.push_str("\\r"),
+            '\0' => output.push_str("\\0"),
+            c if c.is_control() => output.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+impl ConfigConverter for crate::KdlConverter {
+    fn parse(&self, content: &str) -> Result<ConfigValue> {
+        let value = kdl_parser::parse_kdl(content)?;
+        Ok(ConfigValue { value })
+    }
+
+    fn format(&self, config: &ConfigValue) -> Result<String> {
+        // 按 KDL v2 语法输出，映射规则与解析时相反
+        let root: serde_json::Map<String, Value> = config.value.clone().into_iter().collect();
+        let mut output = String::new();
+        for (key, value) in &root {
+            format_kdl_node(&mut output, key, value, 0)?;
+        }
+        Ok(output)
+    }
+}
+
+fn is_kdl_scalar(value: &Value) -> bool {
+    !value.is_array() && !value.is_object()
+}
+
+fn format_kdl_node(output: &mut String, name: &str, value: &Value, indent: usize) -> Result<()> {
+    let indent_str = "    ".repeat(indent);
+    match value {
+        // 多个标量写成同一节点的多个参数
+        Value::Array(arr) if arr.len() >= 2 && arr.iter().all(is_kdl_scalar) => {
+            output.push_str(&format!("{}{}", indent_str, format_kdl_name(name)));
+            for item in arr {
+                output.push_str(&format!(" {}", format_kdl_scalar(item)));
+            }
+            output.push('\n');
+        }
+        // 多个对象写成重复的同名节点
+        Value::Array(arr) if arr.len() >= 2 && arr.iter().all(Value::is_object) => {
+            for item in arr {
+                format_kdl_node(output, name, item, indent)?;
+            }
+        }
+        // 空数组加上 $array 类型注解，否则会读成空对象
+        Value::Array(arr) if arr.is_empty() => {
+            output.push_str(&format!("{}({}){} {{}}\n", indent_str, kdl_parser::ARRAY_TYPE, format_kdl_name(name)));
+        }
+        // 其余数组用 - 子节点表示每个元素
+        Value::Array(arr) => {
+            output.push_str(&format!("{}{} {{\n", indent_str, format_kdl_name(name)));
+            for item in arr {
+                format_kdl_node(output, "-", item, indent + 1)?;
+            }
+            output.push_str(&format!("{}}}\n", indent_str));
+        }
+        Value::Object(obj) => {
+            let ty = obj.get(kdl_parser::TYPE_KEY).and_then(Value::as_str);
+            let prefix = ty.map(|t| format!("({})", format_kdl_name(t))).unwrap_or_default();
+
+            if let (Some(_), Some(inner), 2) = (ty, obj.get(kdl_parser::VALUE_KEY), obj.len()) {
+                if is_kdl_scalar(inner) {
+                    output.push_str(&format!("{}{} {}{}\n", indent_str, format_kdl_name(name), prefix, format_kdl_scalar(inner)));
+                    return Ok(());
+                }
+            }
+
+            output.push_str(&format!("{}{}{}", indent_str, prefix, format_kdl_name(name)));
+            if let Some(Value::Array(args)) = obj.get(kdl_parser::ARGS_KEY) {
+                for arg in args.iter().filter(|a| is_kdl_scalar(a)) {
+                    output.push_str(&format!(" {}", format_kdl_scalar(arg)));
+                }
+            }
+
+            let children: Vec<_> = obj
+                .iter()
+                .filter(|(k, _)| k.as_str() != kdl_parser::TYPE_KEY && k.as_str() != kdl_parser::ARGS_KEY)
+                .collect();
+            if children.is_empty() {
+                output.push_str(" {}\n");
+            } else {
+                output.push_str(" {\n");
+                for (key, child) in children {
+                    format_kdl_node(output, key, child, indent + 1)?;
+                }
+                output.push_str(&format!("{}}}\n", indent_str));
+            }
+        }
+        scalar => output.push_str(&format!("{}{} {}\n", indent_str, format_kdl_name(name), format_kdl_scalar(scalar))),
+    }
+    Ok(())
+}
+
+fn format_kdl_name(name: &str) -> String {
+    // 看起来像数字或关键字的名称必须加引号
+    let looks_like_number = name.starts_with(|c: char| c.is_ascii_digit())
+        || (name.starts_with(['-', '+', '.']) && name.chars().nth(1).is_some_and(|c| c.is_ascii_digit()));
+    let bare = !name.is_empty()
+        && name.chars().all(kdl_parser::is_identifier_char)
+        && !looks_like_number
+        && !matches!(name, "true" | "false" | "null" | "inf" | "-inf" | "nan");
+    if bare {
+        name.to_string()
+    } else {
+        format_kdl_string(name)
+    }
+}
+
+fn format_kdl_scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "#null".to_string(),
+        Value::Bool(b) => format!("#{}", b),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => format_kdl_string(s),
+        other => format_kdl_string(&other.to_string()),
+    }
+}
+
+fn format_kdl_string(s: &str) -> String {
+    let mut result = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            '\u{8}' => result.push_str("\\b"),
+            '\u{c}' => result.push_str("\\f"),
+            c if c.is_control() => result.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+impl ConfigConverter for crate::MessagePackConverter {
+    fn parse(&self, content: &str) -> Result<ConfigValue> {
+        self.parse_bytes(content.as_bytes())
+    }
+
+    fn format(&self, _config: &ConfigValue) -> Result<String> {
+        Err(anyhow!("MessagePack 是二进制格式，请使用 format_bytes"))
+    }
+
+    fn parse_bytes(&self, bytes: &[u8]) -> Result<ConfigValue> {
+        let value = rmp_serde::from_slice(bytes).map_err(|e| anyhow!("MessagePack 解析错误: {}", e))?;
+        Ok(ConfigValue { value })
+    }
+
+    // 对象按键名写成 map，而不是按位置写成数组
+    fn format_bytes(&self, config: &ConfigValue) -> Result<Vec<u8>> {
+        Ok(rmp_serde::to_vec_named(&sorted_root(config))?)
+    }
+}
+
+impl ConfigConverter for crate::CborConverter {
+    fn parse(&self, content: &str) -> Result<ConfigValue> {
+        self.parse_bytes(content.as_bytes())
+    }
+
+    fn format(&self, _config: &ConfigValue) -> Result<String> {
+        Err(anyhow!("CBOR 是二进制格式，请使用 format_bytes"))
+    }
+
+    fn parse_bytes(&self, bytes: &[u8]) -> Result<ConfigValue> {
+        let value = ciborium::from_reader(bytes).map_err(|e| anyhow!("CBOR 解析错误: {}", e))?;
+        Ok(ConfigValue { value })
+    }
+
+    fn format_bytes(&self, config: &ConfigValue) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        ciborium::into_writer(&sorted_root(config), &mut buf).map_err(|e| anyhow!("CBOR 序列化错误: {}", e))?;
+        Ok(buf)
+    }
+}
+
+impl ConfigConverter for crate::GitConfigConverter {
+    fn parse(&self, content: &str) -> Result<ConfigValue> {
+        let value = gitconfig_parser::parse_gitconfig(content)?;
+        Ok(ConfigValue { value })
+    }
+
+    fn format(&self, config: &ConfigValue) -> Result<String> {
+        // 顶层每个键是一个节，节内的对象是子节；先写节自身的变量，再写各个子节
+        let root: serde_json::Map<String, Value> = config.value.clone().into_iter().collect();
+        let mut output = String::new();
+        for (section, value) in &root {
+            let obj = value
+                .as_object()
+                .ok_or_else(|| anyhow!("gitconfig 顶层的 {} 必须是对象", section))?;
+            if section.is_empty() || !section.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                return Err(anyhow!("无效的 gitconfig 节名: {}", section));
+            }
+
+            let (subsections, variables): (Vec<_>, Vec<_>) = obj.iter().partition(|(_, v)| v.is_object());
+            if !variables.is_empty() || subsections.is_empty() {
+                output.push_str(&format!("[{}]\n", section));
+                push_gitconfig_variables(&mut output, section, variables)?;
+            }
+            for (sub, value) in subsections {
+                output.push_str(&format!("[{} {}]\n", section, format_gitconfig_subsection(sub)?));
+                let path = format!("{}.{}", section, sub);
+                let variables: Vec<_> = value.as_object().into_iter().flatten().collect();
+                if let Some((key, _)) = variables.iter().find(|(_, v)| v.is_object()) {
+                    return Err(anyhow!("gitconfig 最多支持节和子节两层: {}.{}", path, key));
+                }
+                push_gitconfig_variables(&mut output, &path, variables)?;
+            }
+        }
+        Ok(output)
+    }
+}
+
+// 数组写成同名变量重复多行
+fn push_gitconfig_variables(output: &mut String, path: &str, variables: Vec<(&String, &Value)>) -> Result<()> {
+    for (key, value) in variables {
+        if !gitconfig_parser::is_variable_name(key) {
+            return Err(anyhow!("无效的 gitconfig 变量名: {}.{}", path, key));
+        }
+        let values = match value {
+            Value::Array(arr) => arr.iter().collect(),
+            other => vec![other],
+        };
+        for value in values {
+            let text = match value {
+                Value::String(s) => format_gitconfig_value(s),
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                Value::Null => return Err(anyhow!("gitconfig 不支持 null: {}.{}", path, key)),
+                _ => return Err(anyhow!("gitconfig 变量的值必须是标量或标量数组: {}.{}", path, key)),
+            };
+            output.push_str(&format!("\t{} = {}\n", key, text));
+        }
+    }
+    Ok(())
+}
+
+fn format_gitconfig_subsection(name: &str) -> Result<String> {
+    if name.contains(['\n', '\0']) {
+        return Err(anyhow!("gitconfig 子节名不能包含换行: {:?}", name));
+    }
+    Ok(format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\"")))
+}
+
+// 首尾空白、连续空白和注释符号只有在引号内才能保留
+fn format_gitconfig_value(s: &str) -> String {
+    let needs_quotes = s.is_empty()
+        || s.starts_with(char::is_whitespace)
+        || s.ends_with(char::is_whitespace)
+        || s.contains("  ")
+        || s.contains(['#', ';']);
+    let mut result = String::new();
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            '\u{8}' => result.push_str("\\b"),
+            c => result.push(c),
+        }
+    }
+    if needs_quotes {
+        format!("\"{}\"", result)
+    } else {
+        result
+    }
+}
+
+impl ConfigConverter for crate::SystemdConverter {
+    fn parse(&self, content: &str) -> Result<ConfigValue> {
+        let value = systemd_parser::parse_systemd(content)?;
+        Ok(ConfigValue { value })
+    }
+
+    fn format(&self, config: &ConfigValue) -> Result<String> {
+        // [Unit] 放在最前、[Install] 放在最后，其余节按名称排序
+        let root: serde_json::Map<String, Value> = config.value.clone().into_iter().collect();
+        let mut sections: Vec<_> = root.iter().collect();
+        sections.sort_by_key(|(name, _)| match name.as_str() {
+            "Unit" => 0,
+            "Install" => 2,
+            _ => 1,
+        });
+
+        let mut output = String::new();
+        for (section, value) in sections {
+            let obj = value
+                .as_object()
+                .ok_or_else(|| anyhow!("systemd 单元顶层的 {} 必须是对象", section))?;
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            output.push_str(&format!("[{}]\n", section));
+            for (key, value) in obj {
+                if !systemd_parser::is_directive_name(key) {
+                    return Err(anyhow!("无效的 systemd 指令名: {}.{}", section, key));
+                }
+                // 数组写成同名指令重复多行，"" 写成空赋值，多行字符串写成续行
+                let values = match value {
+                    Value::Array(arr) => arr.iter().collect(),
+                    other => vec![other],
+                };
+                for value in values {
+                    let text = match value {
+                        Value::String(s) => s.replace('\n', "\\\n"),
+                        Value::Number(n) => n.to_string(),
+                        Value::Bool(b) => b.to_string(),
+                        Value::Null => return Err(anyhow!("systemd 单元不支持 null: {}.{}", section, key)),
+                        _ => return Err(anyhow!("systemd 指令的值必须是标量或标量数组: {}.{}", section, key)),
+                    };
+                    output.push_str(&format!("{}={}\n", key, text));
+                }
+            }
+        }
+        Ok(output)
+    }
+}
+
+// 以 configparser 读取 INI 方言：区分大小写，只以 = 分隔，不支持行内注释；第一个节之前的键放在名为 "" 的节中
+type IniSections = HashMap<String, HashMap<String, Option<String>>>;
+
+fn read_ini_sections(content: &str, comment_symbols: &[char], dialect: &str) -> Result<IniSections> {
+    let mut defaults = IniDefault::default();
+    defaults.default_section = String::new();
+    defaults.comment_symbols = comment_symbols.to_vec();
+    defaults.delimiters = vec!['='];
+    defaults.case_sensitive = true;
+    defaults.enable_inline_comments = false;
+    let mut ini = Ini::new_from_defaults(defaults);
+    ini.read(content.to_string()).map_err(|e| anyhow!("{} 解析错误: {}", dialect, e))
+}
+
+impl ConfigConverter for crate::EditorConfigConverter {
+    fn parse(&self, content: &str) -> Result<ConfigValue> {
+        // 节名是 glob，节之前的 root = true 放在顶层；整数和 true/false 转换为对应类型
+        let mut value = HashMap::new();
+        for (section, props) in read_ini_sections(content, &['#', ';'], "EditorConfig")? {
+            let props: serde_json::Map<String, Value> = props
+                .into_iter()
+                .map(|(k, v)| (k, editorconfig_value(v.unwrap_or_default())))
+                .collect();
+            if section.is_empty() {
+                value.extend(props);
+            } else {
+                value.insert(section, Value::Object(props));
+            }
+        }
+        Ok(ConfigValue { value })
+    }
+
+    fn format(&self, config: &ConfigValue) -> Result<String> {
+        let root: serde_json::Map<String, Value> = config.value.clone().into_iter().collect();
+        let (sections, preamble): (Vec<_>, Vec<_>) = root.iter().partition(|(_, v)| v.is_object());
+
+        let mut output = String::new();
+        for (key, value) in preamble {
+            output.push_str(&format!("{} = {}\n", key, format_editorconfig_value(key, value)?));
+        }
+
+        // 节的先后顺序决定覆盖关系，无法从对象中还原；[*] 放在最前，其余按 glob 排序
+        let mut sections = sections;
+        sections.sort_by_key(|(glob, _)| glob.as_str() != "*");
+        for (glob, props) in sections {
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            output.push_str(&format!("[{}]\n", glob));
+            for (key, value) in props.as_object().into_iter().flatten() {
+                output.push_str(&format!("{} = {}\n", key, format_editorconfig_value(key, value)?));
+            }
+        }
+        Ok(output)
+    }
+}
+
+fn editorconfig_value(text: String) -> Value {
+    match text.to_lowercase().as_str() {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => text.parse::<u64>().map(Value::from).unwrap_or(Value::String(text)),
+    }
+}
+
+fn format_editorconfig_value(key: &str, value: &Value) -> Result<String> {
+    match value {
+        Value::String(s) if !s.contains('\n') => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        _ => Err(anyhow!("EditorConfig 属性的值必须是单行标量: {}", key)),
+    }
+}
+
+// desktop entry 中以 ; 分隔的列表键，其余键只有以 ; 结尾时才按列表处理
+const DESKTOP_LIST_KEYS: &[&str] = &["Actions", "Categories", "Implements", "Keywords", "MimeType", "NotShowIn", "OnlyShowIn"];
+// 本地化键 Name[de] 与未本地化的 Name 合并为对象时，未本地化的值放在这个键下
+const DESKTOP_DEFAULT_LOCALE_KEY: &str = "$value";
+
+impl ConfigConverter for crate::DesktopEntryConverter {
+    fn parse(&self, content: &str) -> Result<ConfigValue> {
+        // 本地化键 Name[de] 映射为 "Name": {"$value": ..., "de": ...}，列表映射为数组，true/false 映射为布尔值
+        let mut value = HashMap::new();
+        for (section, props) in read_ini_sections(content, &['#'], "desktop entry")? {
+            if section.is_empty() && !props.is_empty() {
+                return Err(anyhow!("desktop entry 的键必须位于节内"));
+            }
+
+            let mut entries = serde_json::Map::new();
+            let mut props: Vec<_> = props.into_iter().collect();
+            // 先处理未本地化的键，保证本地化变体能合并进去
+            props.sort_by_key(|(k, _)| k.contains('['));
+            for (key, raw) in props {
+                let raw = raw.unwrap_or_default();
+                let (name, locale) = match key.strip_suffix(']').and_then(|k| k.split_once('[')) {
+                    Some((name, locale)) => (name.to_string(), Some(locale.to_string())),
+                    None => (key, None),
+                };
+                let parsed = desktop_value(&name, &raw)?;
+                match locale {
+                    None => {
+                        entries.insert(name, parsed);
+                    }
+                    Some(locale) => {
+                        let slot = entries.entry(name).or_insert_with(|| Value::Object(serde_json::Map::new()));
+                        if !slot.is_object() {
+                            *slot = Value::Object([(DESKTOP_DEFAULT_LOCALE_KEY.to_string(), slot.take())].into_iter().collect());
+                        }
+                        if let Value::Object(variants) = slot {
+                            variants.insert(locale, parsed);
+                        }
+                    }
+                }
+            }
+            if !section.is_empty() {
+                value.insert(section, Value::Object(entries));
+            }
+        }
+        Ok(ConfigValue { value })
+    }
+
+    fn format(&self, config: &ConfigValue) -> Result<String> {
+        // [Desktop Entry] 放在最前，其余节（如 [Desktop Action new]）按名称排序
+        let root: serde_json::Map<String, Value> = config.value.clone().into_iter().collect();
+        let mut sections: Vec<_> = root.iter().collect();
+        sections.sort_by_key(|(name, _)| name.as_str() != "Desktop Entry");
+
+        let mut output = String::new();
+        for (section, value) in sections {
+            let entries = value
+                .as_object()
+                .ok_or_else(|| anyhow!("desktop entry 顶层的 {} 必须是对象", section))?;
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            output.push_str(&format!("[{}]\n", section));
+            for (key, value) in entries {
+                match value {
+                    Value::Object(variants) => {
+                        if let Some(default) = variants.get(DESKTOP_DEFAULT_LOCALE_KEY) {
+                            output.push_str(&format!("{}={}\n", key, format_desktop_value(key, default)?));
+                        }
+                        for (locale, variant) in variants.iter().filter(|(l, _)| l.as_str() != DESKTOP_DEFAULT_LOCALE_KEY) {
+                            output.push_str(&format!("{}[{}]={}\n", key, locale, format_desktop_value(key, variant)?));
+                        }
+                    }
+                    other => output.push_str(&format!("{}={}\n", key, format_desktop_value(key, other)?)),
+                }
+            }
+        }
+        Ok(output)
+    }
+}
+
+fn desktop_value(key: &str, raw: &str) -> Result<Value> {
+    if DESKTOP_LIST_KEYS.contains(&key) || (raw.ends_with(';') && !raw.ends_with("\\;")) {
+        let mut items = Vec::new();
+        let mut current = String::new();
+        let mut chars = raw.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some(';') => current.push(';'),
+                    Some(escaped) => current.push_str(&unescape_desktop(&format!("\\{}", escaped))),
+                    None => current.push('\\'),
+                },
+                ';' => items.push(Value::String(std::mem::take(&mut current))),
+                c => current.push(c),
+            }
+        }
+        if !current.is_empty() {
+            items.push(Value::String(current));
+        }
+        return Ok(Value::Array(items));
+    }
+    Ok(match raw {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => Value::String(unescape_desktop(raw)),
+    })
+}
+
+fn unescape_desktop(raw: &str) -> String {
+    let mut result = String::new();
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => result.push(' '),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('\\') => result.push('\\'),
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+fn format_desktop_value(key: &str, value: &Value) -> Result<String> {
+    match value {
+        // 列表的每一项都以 ; 结尾
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(s) => Ok(format!("{};", escape_desktop(s).replace(';', "\\;"))),
+                Value::Number(_) | Value::Bool(_) => Ok(format!("{};", item)),
+                _ => Err(anyhow!("desktop entry 列表的元素必须是标量: {}", key)),
+            })
+            .collect(),
+        Value::String(s) => Ok(escape_desktop(s)),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        _ => Err(anyhow!("desktop entry 的值必须是标量、列表或本地化对象: {}", key)),
+    }
+}
+
+fn escape_desktop(s: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in s.chars().enumerate() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            '\r' => result.push_str("\\r"),
+            // 行首空格会被读取方去掉，需要写成 \s
+            ' ' if i == 0 => result.push_str("\\s"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+// CSV 解析结果放在这个顶层键下；输出时顶层只要有唯一一个对象数组即可，键名不限
+const CSV_ROWS_KEY: &str = "rows";
+
+impl ConfigConverter for crate::CsvConverter {
+    fn parse(&self, content: &str) -> Result<ConfigValue> {
+        // 首行是表头，带点号的列名 db.host 还原为嵌套对象
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(self.options.delimiter)
+            .from_reader(content.as_bytes());
+        let headers: Vec<Vec<String>> = reader
+            .headers()
+            .map_err(|e| anyhow!("CSV 解析错误: {}", e))?
+            .iter()
+            .map(|h| h.split('.').map(String::from).collect())
+            .collect();
+
+        let mut rows = Vec::new();
+        for (index, record) in reader.records().enumerate() {
+            let record = record.map_err(|e| anyhow!("CSV 解析错误: {}", e))?;
+            let mut row = Value::Object(serde_json::Map::new());
+            for (path, cell) in headers.iter().zip(record.iter()) {
+                let cell = if self.options.infer_types {
+                    infer_csv_cell(cell)
+                } else {
+                    Value::String(cell.to_string())
+                };
+                insert_csv_cell(&mut row, path, cell)
+                    .map_err(|_| anyhow!("第 {} 行: 列名冲突: {}", index + 2, path.join(".")))?;
+            }
+            rows.push(row);
+        }
+
+        let mut value = HashMap::new();
+        value.insert(CSV_ROWS_KEY.to_string(), Value::Array(rows));
+        Ok(ConfigValue { value })
+    }
+
+    fn format(&self, config: &ConfigValue) -> Result<String> {
+        let rows = match config.value.values().next() {
+            Some(Value::Array(rows)) if config.value.len() == 1 => rows,
+            _ => return Err(anyhow!("CSV 只能表示表格数据：顶层必须只有一个键，且其值为对象数组")),
+        };
+
+        // 嵌套对象展开为 db.host 形式的列，列的顺序按首次出现的顺序
+        let mut headers: Vec<String> = Vec::new();
+        let mut cells: Vec<HashMap<String, String>> = Vec::new();
+        for (index, row) in rows.iter().enumerate() {
+            let obj = row
+                .as_object()
+                .ok_or_else(|| anyhow!("CSV 的每一行必须是对象，第 {} 个元素是 {}", index + 1, row))?;
+            let mut flat = Vec::new();
+            for (key, value) in obj {
+                flatten_csv_cell(key, value, &mut flat).map_err(|e| anyhow!("第 {} 个元素: {}", index + 1, e))?;
+            }
+            for (key, _) in &flat {
+                if !headers.contains(key) {
+                    headers.push(key.clone());
+                }
+            }
+            cells.push(flat.into_iter().collect());
+        }
+
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(self.options.delimiter)
+            .from_writer(Vec::new());
+        if !headers.is_empty() {
+            writer.write_record(&headers)?;
+        }
+        for row in &cells {
+            writer.write_record(headers.iter().map(|h| row.get(h).map(String::as_str).unwrap_or("")))?;
+        }
+        Ok(String::from_utf8(writer.into_inner()?)?)
+    }
+}
+
+fn infer_csv_cell(cell: &str) -> Value {
+    if cell.is_empty() {
+        return Value::Null;
+    }
+    match cell {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => cell
+            .parse::<i64>()
+            .map(Value::from)
+            .ok()
+            .or_else(|| cell.parse::<f64>().ok().filter(|f| f.is_finite()).map(Value::from))
+            .unwrap_or_else(|| Value::String(cell.to_string())),
+    }
+}
+
+fn insert_csv_cell(target: &mut Value, path: &[String], cell: Value) -> Result<()> {
+    let obj = target.as_object_mut().ok_or_else(|| anyhow!("列名冲突"))?;
+    match path {
+        [] => Err(anyhow!("列名冲突")),
+        [last] if obj.contains_key(last) => Err(anyhow!("列名冲突")),
+        [last] => {
+            obj.insert(last.clone(), cell);
+            Ok(())
+        }
+        [first, rest @ ..] => {
+            let child = obj.entry(first.clone()).or_insert_with(|| Value::Object(serde_json::Map::new()));
+            insert_csv_cell(child, rest, cell)
+        }
+    }
+}
+
+fn flatten_csv_cell(key: &str, value: &Value, output: &mut Vec<(String, String)>) -> Result<()> {
+    match value {
+        Value::Object(obj) => {
+            for (k, v) in obj {
+                flatten_csv_cell(&format!("{}.{}", key, k), v, output)?;
+            }
+        }
+        Value::Array(_) => return Err(anyhow!("CSV 单元格不能是数组: {}", key)),
+        Value::Null => output.push((key.to_string(), String::new())),
+        Value::String(s) => output.push((key.to_string(), s.clone())),
+        other => output.push((key.to_string(), other.to_string())),
+    }
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use crate::{json5_parser, ConfigFormat};
+use std::collections::HashMap;
+
+// 根据内容为各候选格式打分：先按行累计结构特征，再用严格的解析器排除解析失败的候选。
+// 结果按得分从高到低排列，只包含得分大于 0 的格式
+pub fn score_formats(content: &str) -> Vec<(ConfigFormat, u32)> {
+    let content = content.trim_start_matches('\u{feff}').trim();
+    if content.is_empty() {
+        return Vec::new();
+    }
+
+    // XML 和 JSON 的外层结构足以直接判断
+    if content.starts_with('<') {
+        if content.contains("<plist") {
+            return vec![(ConfigFormat::Plist, 10), (ConfigFormat::Xml, 5)];
+        }
+        return vec![(ConfigFormat::Xml, 10)];
+    }
+    if content.starts_with('{') && serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(content).is_ok() {
+        return vec![(ConfigFormat::Json, 10)];
+    }
+
+    let mut scores: HashMap<ConfigFormat, u32> = HashMap::new();
+    let mut add = |format: ConfigFormat, points: u32| *scores.entry(format).or_insert(0) += points;
+    if content.starts_with('{') && json5_parser::parse_json5(content).is_ok() {
+        add(ConfigFormat::Json5, 8);
+    }
+
+    // 每一行都必须符合 dotenv 语法才可能是 ENV；INI 至少要有一个节
+    let mut env_ok = true;
+    let mut has_section = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with("//") {
+            add(ConfigFormat::Hocon, 1);
+            env_ok = false;
+            continue;
+        }
+        if line.starts_with(';') {
+            add(ConfigFormat::Ini, 1);
+            env_ok = false;
+            continue;
+        }
+
+        if line.starts_with("[[") && line.ends_with("]]") {
+            add(ConfigFormat::Toml, 2);
+            has_section = true;
+            env_ok = false;
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            add(ConfigFormat::Toml, 1);
+            add(ConfigFormat::Ini, 1);
+            has_section = true;
+            env_ok = false;
+            continue;
+        }
+        if line == "-" || line.starts_with("- ") {
+            add(ConfigFormat::Yaml, 2);
+            env_ok = false;
+            continue;
+        }
+
+        let line = match line.strip_prefix("export ") {
+            Some(rest) => {
+                add(ConfigFormat::Env, 2);
+                rest.trim_start()
+            }
+            None => line,
+        };
+        let Some(position) = line.find(['=', ':', '{']) else {
+            env_ok = false;
+            continue;
+        };
+        let key = &line[..position];
+        let value = line[position + 1..].trim();
+        if key.trim().is_empty() || !key.trim().chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '"' | '\'')) {
+            env_ok = false;
+            continue;
+        }
+
+        match &line[position..position + 1] {
+            "=" => {
+                // dotenv 的键通常是大写且等号两侧没有空格
+                if is_env_key(key) {
+                    add(ConfigFormat::Env, 4);
+                } else {
+                    env_ok = false;
+                }
+                add(ConfigFormat::Ini, 1);
+                add(ConfigFormat::Hocon, 1);
+                if is_toml_literal(value) {
+                    add(ConfigFormat::Toml, 1);
+                }
+                if value == "{" || value.contains("${") {
+                    add(ConfigFormat::Hocon, 2);
+                }
+            }
+            ":" => {
+                add(ConfigFormat::Yaml, 2);
+                add(ConfigFormat::Hocon, 1);
+                env_ok = false;
+            }
+            _ => {
+                add(ConfigFormat::Hocon, 2);
+                env_ok = false;
+            }
+        }
+    }
+
+    if !env_ok {
+        scores.remove(&ConfigFormat::Env);
+    }
+    if !has_section {
+        scores.remove(&ConfigFormat::Ini);
+    }
+    // 语法严格的格式用解析器验证：能解析则加分，否则排除。HOCON、INI、ENV 的解析器过于宽松，不参与验证
+    if scores.contains_key(&ConfigFormat::Toml) {
+        validate(&mut scores, ConfigFormat::Toml, 3, toml::from_str::<toml::Table>(content).is_ok());
+    }
+    if scores.contains_key(&ConfigFormat::Yaml) {
+        validate(&mut scores, ConfigFormat::Yaml, 3, serde_yaml::from_str::<serde_yaml::Mapping>(content).is_ok());
+    }
+
+    let mut result: Vec<_> = scores.into_iter().filter(|(_, score)| *score > 0).collect();
+    result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.to_extension().cmp(b.0.to_extension())));
+    result
+}
+
+fn validate(scores: &mut HashMap<ConfigFormat, u32>, format: ConfigFormat, bonus: u32, valid: bool) {
+    if valid {
+        *scores.entry(format).or_insert(0) += bonus;
+    } else {
+        scores.remove(&format);
+    }
+}
+
+fn is_env_key(key: &str) -> bool {
+    key.starts_with(|c: char| c.is_ascii_uppercase() || c == '_')
+        && key.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+// TOML 的值必须是字面量：字符串、数字、布尔值、日期、数组或内联表
+fn is_toml_literal(value: &str) -> bool {
+    let value = value.split(" #").next().unwrap_or(value).trim();
+    value.starts_with(['"', '\'', '[', '{'])
+        || matches!(value, "true" | "false" | "inf" | "nan" | "+inf" | "-inf")
+        || value.replace('_', "").parse::<f64>().is_ok()
+        || (value.as_bytes().get(4) == Some(&b'-') && value.get(..4).is_some_and(|y| y.chars().all(|c| c.is_ascii_digit())))
+}
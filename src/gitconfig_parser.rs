@@ -0,0 +1,167 @@
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+// git-config 解析器。与 JSON 的映射规则：
+// - `[section]` 映射为对象，`[section "sub"]` 和旧式 `[section.sub]` 映射为 section 下的嵌套对象
+// - 同一节重复出现时合并；同一变量重复出现时合并为数组（如多个 fetch、include.path）
+// - 没有 `=` 的变量 `bare` 表示 true；其余值一律保留为字符串，不做类型推断
+pub fn parse_gitconfig(input: &str) -> Result<HashMap<String, Value>> {
+    let mut root = Map::new();
+    let mut section: Option<(String, Option<String>)> = None;
+    let mut lines = input.lines().enumerate();
+
+    while let Some((index, line)) = lines.next() {
+        let mut rest = line.trim_start();
+        if rest.starts_with('[') {
+            let (header, remainder) = parse_header(rest).map_err(|e| anyhow!("第 {} 行: {}", index + 1, e))?;
+            section = Some(header);
+            // 节头后面可以紧跟一个变量：[core] bare = true
+            rest = remainder.trim_start();
+        }
+        if rest.is_empty() || rest.starts_with(['#', ';']) {
+            continue;
+        }
+
+        let Some((name, sub)) = &section else {
+            return Err(anyhow!("第 {} 行: 变量必须位于节内", index + 1));
+        };
+
+        let key_end = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '-')).unwrap_or(rest.len());
+        let key = &rest[..key_end];
+        if !is_variable_name(key) {
+            return Err(anyhow!("第 {} 行: 无效的变量名: {}", index + 1, rest));
+        }
+
+        let after = rest[key_end..].trim_start();
+        let value = if after.is_empty() || after.starts_with(['#', ';']) {
+            Value::Bool(true)
+        } else if let Some(raw) = after.strip_prefix('=') {
+            // 以反斜杠结尾的行与下一行拼接
+            let mut raw = raw.to_string();
+            while raw.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1 {
+                raw.pop();
+                match lines.next() {
+                    Some((_, next)) => raw.push_str(next),
+                    None => break,
+                }
+            }
+            Value::String(parse_value(&raw).map_err(|e| anyhow!("第 {} 行: {}", index + 1, e))?)
+        } else {
+            return Err(anyhow!("第 {} 行: 缺少 =: {}", index + 1, rest));
+        };
+
+        let target = section_object(&mut root, name, sub.as_deref())?;
+        match target.get_mut(key) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(Value::Object(_)) => return Err(anyhow!("键冲突: {}.{}", name, key)),
+            Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+            None => {
+                target.insert(key.to_string(), value);
+            }
+        }
+    }
+
+    Ok(root.into_iter().collect())
+}
+
+fn section_object<'a>(root: &'a mut Map<String, Value>, name: &str, sub: Option<&str>) -> Result<&'a mut Map<String, Value>> {
+    let mut target = root
+        .entry(name.to_string())
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("键冲突: {}", name))?;
+    if let Some(sub) = sub {
+        target = target
+            .entry(sub.to_string())
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("键冲突: {}.{}", name, sub))?;
+    }
+    Ok(target)
+}
+
+// 解析 [section]、[section "sub"] 或 [section.sub]，返回节名和剩余文本
+fn parse_header(line: &str) -> Result<((String, Option<String>), &str)> {
+    let inner = &line[1..];
+    let name_end = inner.find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '.')).unwrap_or(inner.len());
+    let name = &inner[..name_end];
+    if name.is_empty() {
+        return Err(anyhow!("无效的节名: {}", line));
+    }
+
+    let after = &inner[name_end..];
+    if let Some(remainder) = after.strip_prefix(']') {
+        return Ok(match name.split_once('.') {
+            Some((section, sub)) => ((section.to_string(), Some(sub.to_string())), remainder),
+            None => ((name.to_string(), None), remainder),
+        });
+    }
+
+    let quoted = after
+        .trim_start()
+        .strip_prefix('"')
+        .ok_or_else(|| anyhow!("无效的节头: {}", line))?;
+    let mut sub = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, escaped)) => sub.push(escaped),
+                None => break,
+            },
+            '"' => {
+                let remainder = quoted[i + 1..]
+                    .strip_prefix(']')
+                    .ok_or_else(|| anyhow!("节头缺少 ]: {}", line))?;
+                return Ok(((name.to_string(), Some(sub)), remainder));
+            }
+            c => sub.push(c),
+        }
+    }
+    Err(anyhow!("未闭合的子节名: {}", line))
+}
+
+// 按 git 的规则解析值：引号内原样保留，引号外的连续空白压缩为一个空格，# 和 ; 开始注释
+fn parse_value(raw: &str) -> Result<String> {
+    let mut result = String::new();
+    let mut quoted = false;
+    let mut pending_space = false;
+    let mut chars = raw.trim_start().chars();
+
+    while let Some(c) = chars.next() {
+        if !quoted && c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+        if !quoted && (c == '#' || c == ';') {
+            break;
+        }
+        if pending_space && !result.is_empty() {
+            result.push(' ');
+        }
+        pending_space = false;
+        match c {
+            '"' => quoted = !quoted,
+            '\\' => match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some('b') => result.push('\u{8}'),
+                Some(escaped @ ('"' | '\\')) => result.push(escaped),
+                Some(other) => return Err(anyhow!("无效的转义: \\{}", other)),
+                None => break,
+            },
+            c => result.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(anyhow!("未闭合的引号: {}", raw.trim()));
+    }
+    Ok(result)
+}
+
+// 变量名只能包含字母、数字和 -，且必须以字母开头
+pub fn is_variable_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
@@ -0,0 +1,498 @@
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+// HCL2 解析器。属性映射为键值，块按类型和标签逐层嵌套为对象，同名同标签的块合并为数组。
+// 字面量（字符串、数字、布尔、null、元组、对象、heredoc）转换为对应的 JSON 值，
+// 其他表达式（引用、函数调用、条件、运算、for 表达式等）按 HCL JSON 语法的约定保存为 "${表达式}" 字符串。
+
+#[derive(Clone, Copy, PartialEq)]
+enum Context {
+    // 属性值：到行尾或所在块的 } 为止
+    Body,
+    // 元组元素：到 , 或 ] 为止
+    Tuple,
+    // 对象元素：到 , 换行或 } 为止
+    Object,
+    // 括号内部：只到未配对的右括号为止
+    Group,
+}
+
+struct Parser {
+    input: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Self {
+        Parser {
+            input: input.chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input.get(self.position).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.input.get(self.position + offset).copied()
+    }
+
+    fn error(&self, message: &str) -> anyhow::Error {
+        let consumed = &self.input[..self.position.min(self.input.len())];
+        let line = consumed.iter().filter(|&&c| c == '\n').count() + 1;
+        anyhow!("HCL 解析错误: {} (第 {} 行)", message, line)
+    }
+
+    fn skip_inline_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ') | Some('\t') | Some('\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) -> Result<()> {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => self.position += 1,
+                Some('#') => self.skip_line_comment(),
+                Some('/') if self.peek_at(1) == Some('/') => self.skip_line_comment(),
+                Some('/') if self.peek_at(1) == Some('*') => self.skip_block_comment()?,
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn skip_line_comment(&mut self) {
+        while self.peek().is_some_and(|c| c != '\n') {
+            self.position += 1;
+        }
+    }
+
+    fn skip_block_comment(&mut self) -> Result<()> {
+        self.position += 2;
+        loop {
+            match self.peek() {
+                Some('*') if self.peek_at(1) == Some('/') => {
+                    self.position += 2;
+                    return Ok(());
+                }
+                Some(_) => self.position += 1,
+                None => return Err(self.error("未闭合的注释")),
+            }
+        }
+    }
+
+    fn read_identifier(&mut self) -> String {
+        let mut result = String::new();
+        while let Some(c) = self.peek() {
+            if is_identifier_char(c) {
+                result.push(c);
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+        result
+    }
+
+    fn parse_body(&mut self, top: bool) -> Result<Map<String, Value>> {
+        let mut body = Map::new();
+
+        loop {
+            self.skip_whitespace_and_comments()?;
+            match self.peek() {
+                None if top => return Ok(body),
+                None => return Err(self.error("缺少 }")),
+                Some('}') if !top => {
+                    self.position += 1;
+                    return Ok(body);
+                }
+                _ => {}
+            }
+
+            let name = self.read_identifier();
+            if name.is_empty() {
+                return Err(self.error(&format!("无效的字符: {}", self.peek().unwrap_or(' '))));
+            }
+            self.skip_inline_whitespace();
+
+            if self.peek() == Some('=') && self.peek_at(1) != Some('=') {
+                self.position += 1;
+                let raw = self.scan_expression(Context::Body)?;
+                let value = literal(&raw)?;
+                if body.insert(name.clone(), value).is_some() {
+                    return Err(self.error(&format!("重复的属性: {}", name)));
+                }
+                continue;
+            }
+
+            let mut labels = Vec::new();
+            loop {
+                match self.peek() {
+                    Some('"') => {
+                        let start = self.position;
+                        self.position = skip_string(&self.input, start).ok_or_else(|| self.error("未闭合的字符串"))?;
+                        let raw: String = self.input[start..self.position].iter().collect();
+                        labels.push(unquote(&raw)?);
+                    }
+                    Some(c) if is_identifier_char(c) => labels.push(self.read_identifier()),
+                    Some('{') => break,
+                    _ => return Err(self.error(&format!("期望 = 或 {{: {}", name))),
+                }
+                self.skip_inline_whitespace();
+            }
+            self.position += 1; // 跳过 {
+
+            let block = self.parse_body(false)?;
+            insert_block(&mut body, &name, &labels, block).map_err(|e| self.error(&e.to_string()))?;
+        }
+    }
+
+    // 截取一个完整的表达式原文，括号、字符串、heredoc 内部的分隔符不算结束
+    fn scan_expression(&mut self, context: Context) -> Result<String> {
+        let start = self.position;
+        let mut depth = 0usize;
+
+        while let Some(c) = self.peek() {
+            match c {
+                '"' => {
+                    self.position = skip_string(&self.input, self.position).ok_or_else(|| self.error("未闭合的字符串"))?;
+                    continue;
+                }
+                '<' if self.peek_at(1) == Some('<') && heredoc_marker(&self.input, self.position).is_some() => {
+                    self.position = skip_heredoc(&self.input, self.position).ok_or_else(|| self.error("未闭合的 heredoc"))?;
+                    continue;
+                }
+                '#' | '/' if c == '#' || matches!(self.peek_at(1), Some('/') | Some('*')) => {
+                    // 表达式末尾的注释不属于表达式，括号内部的注释原样保留
+                    if depth == 0 && context != Context::Group {
+                        break;
+                    }
+                    if self.peek_at(1) == Some('*') {
+                        self.skip_block_comment()?;
+                    } else {
+                        self.skip_line_comment();
+                    }
+                    continue;
+                }
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' if depth > 0 => depth -= 1,
+                ')' | ']' | '}' => break,
+                '\n' if depth == 0 && matches!(context, Context::Body | Context::Object) => break,
+                ',' if depth == 0 && matches!(context, Context::Tuple | Context::Object) => break,
+                _ => {}
+            }
+            self.position += 1;
+        }
+
+        let raw: String = self.input[start..self.position].iter().collect();
+        Ok(raw.trim().to_string())
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+// 从 start 处的 " 开始跳过整个字符串模板，返回结束位置；${ } 中可以嵌套字符串
+fn skip_string(chars: &[char], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '"' => return Some(i + 1),
+            '$' | '%' if chars.get(i + 1) == Some(&'{') => {
+                i = skip_template(chars, i + 2)?;
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+// 跳过 ${ 或 %{ 之后的模板内容，返回 } 之后的位置
+fn skip_template(chars: &[char], start: usize) -> Option<usize> {
+    let mut i = start;
+    let mut depth = 1;
+    while i < chars.len() {
+        match chars[i] {
+            '"' => {
+                i = skip_string(chars, i)?;
+                continue;
+            }
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+// 识别 <<EOF 或 <<-EOF，返回 (标记, 是否去除缩进, 标记行结束位置)
+fn heredoc_marker(chars: &[char], start: usize) -> Option<(String, bool, usize)> {
+    let mut i = start + 2;
+    let strip = chars.get(i) == Some(&'-');
+    if strip {
+        i += 1;
+    }
+    let mut marker = String::new();
+    while let Some(&c) = chars.get(i) {
+        if c.is_alphanumeric() || c == '_' {
+            marker.push(c);
+            i += 1;
+        } else {
+            break;
+        }
+    }
+    while chars.get(i) == Some(&'\r') {
+        i += 1;
+    }
+    if marker.is_empty() || chars.get(i) != Some(&'\n') {
+        return None;
+    }
+    Some((marker, strip, i + 1))
+}
+
+fn skip_heredoc(chars: &[char], start: usize) -> Option<usize> {
+    let (marker, _, mut i) = heredoc_marker(chars, start)?;
+    while i <= chars.len() {
+        let line_end = chars[i..].iter().position(|&c| c == '\n').map(|p| i + p).unwrap_or(chars.len());
+        let line: String = chars[i..line_end].iter().collect();
+        if line.trim() == marker {
+            return Some(line_end);
+        }
+        if line_end == chars.len() {
+            return None;
+        }
+        i = line_end + 1;
+    }
+    None
+}
+
+fn parse_heredoc(raw: &str) -> Result<String> {
+    let chars: Vec<char> = raw.chars().collect();
+    let (marker, strip, start) = heredoc_marker(&chars, 0).ok_or_else(|| anyhow!("无效的 heredoc"))?;
+    let body: String = chars[start..].iter().collect();
+    let mut lines: Vec<&str> = body.lines().collect();
+    if lines.last().map(|l| l.trim()) == Some(marker.as_str()) {
+        lines.pop();
+    }
+
+    if strip {
+        // 缩进按字符计数，行首可能是全角空格等多字节空白
+        let indent = lines
+            .iter()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| l.chars().take_while(|c| c.is_whitespace()).count())
+            .min()
+            .unwrap_or(0);
+        lines = lines
+            .iter()
+            .map(|l| {
+                let end = l
+                    .char_indices()
+                    .take_while(|(_, c)| c.is_whitespace())
+                    .take(indent)
+                    .last()
+                    .map_or(0, |(i, c)| i + c.len_utf8());
+                &l[end..]
+            })
+            .collect();
+    }
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    Ok(result)
+}
+
+// 解码带引号的字符串，保留 ${ } 和 %{ } 模板原文
+fn unquote(raw: &str) -> Result<String> {
+    let inner = raw
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or_else(|| anyhow!("无效的字符串: {}", raw))?;
+
+    let mut result = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('"') => result.push('"'),
+            Some('\\') => result.push('\\'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let code = u32::from_str_radix(&hex, 16).map_err(|_| anyhow!("无效的 Unicode 转义: \\u{}", hex))?;
+                result.push(char::from_u32(code).ok_or_else(|| anyhow!("无效的 Unicode 转义: \\u{}", hex))?);
+            }
+            Some('U') => {
+                let hex: String = chars.by_ref().take(8).collect();
+                let code = u32::from_str_radix(&hex, 16).map_err(|_| anyhow!("无效的 Unicode 转义: \\U{}", hex))?;
+                result.push(char::from_u32(code).ok_or_else(|| anyhow!("无效的 Unicode 转义: \\U{}", hex))?);
+            }
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    Ok(result)
+}
+
+// 将表达式原文转换为 JSON 值
+fn literal(raw: &str) -> Result<Value> {
+    let chars: Vec<char> = raw.chars().collect();
+    if chars.is_empty() {
+        return Err(anyhow!("HCL 解析错误: 缺少值"));
+    }
+
+    match raw {
+        "true" => return Ok(Value::Bool(true)),
+        "false" => return Ok(Value::Bool(false)),
+        "null" => return Ok(Value::Null),
+        _ => {}
+    }
+
+    if chars[0] == '"' && skip_string(&chars, 0) == Some(chars.len()) {
+        return Ok(Value::String(unquote(raw)?));
+    }
+    if raw.starts_with("<<") && skip_heredoc(&chars, 0).is_some() {
+        return Ok(Value::String(parse_heredoc(raw)?));
+    }
+    if let Ok(n) = raw.parse::<i64>() {
+        return Ok(Value::Number(Number::from(n)));
+    }
+    if raw.chars().next().is_some_and(|c| c.is_ascii_digit() || c == '-') {
+        if let Some(n) = raw.parse::<f64>().ok().and_then(Number::from_f64) {
+            return Ok(Value::Number(n));
+        }
+    }
+    if (chars[0] == '[' || chars[0] == '{') && matching_close(&chars) == Some(chars.len() - 1) && !is_for_expression(raw) {
+        let mut parser = Parser::new(&raw[1..raw.len() - 1]);
+        return if chars[0] == '[' { parse_tuple(&mut parser) } else { parse_object(&mut parser) };
+    }
+
+    Ok(Value::String(format!("${{{}}}", raw)))
+}
+
+fn is_for_expression(raw: &str) -> bool {
+    raw[1..].trim_start().starts_with("for ")
+}
+
+fn matching_close(chars: &[char]) -> Option<usize> {
+    let mut parser = Parser {
+        input: chars.to_vec(),
+        position: 1,
+    };
+    parser.scan_expression(Context::Group).ok()?;
+    if parser.peek() == Some(if chars[0] == '[' { ']' } else { '}' }) {
+        Some(parser.position)
+    } else {
+        None
+    }
+}
+
+fn parse_tuple(parser: &mut Parser) -> Result<Value> {
+    let mut items = Vec::new();
+    loop {
+        parser.skip_whitespace_and_comments()?;
+        if parser.peek().is_none() {
+            return Ok(Value::Array(items));
+        }
+        let raw = parser.scan_expression(Context::Tuple)?;
+        items.push(literal(&raw)?);
+        parser.skip_whitespace_and_comments()?;
+        match parser.peek() {
+            Some(',') => parser.position += 1,
+            None => return Ok(Value::Array(items)),
+            _ => return Err(parser.error("元组元素之间缺少 ,")),
+        }
+    }
+}
+
+fn parse_object(parser: &mut Parser) -> Result<Value> {
+    let mut map = Map::new();
+    loop {
+        parser.skip_whitespace_and_comments()?;
+        match parser.peek() {
+            None => return Ok(Value::Object(map)),
+            Some(',') => {
+                parser.position += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        let key = match parser.peek() {
+            Some('"') => {
+                let start = parser.position;
+                parser.position = skip_string(&parser.input, start).ok_or_else(|| parser.error("未闭合的字符串"))?;
+                let raw: String = parser.input[start..parser.position].iter().collect();
+                unquote(&raw)?
+            }
+            Some('(') => {
+                let start = parser.position;
+                parser.position += 1;
+                parser.scan_expression(Context::Group)?;
+                parser.position += 1;
+                let raw: String = parser.input[start..parser.position].iter().collect();
+                format!("${{{}}}", raw)
+            }
+            _ => parser.read_identifier(),
+        };
+        if key.is_empty() {
+            return Err(parser.error("无效的对象键"));
+        }
+
+        parser.skip_inline_whitespace();
+        match parser.peek() {
+            Some('=') | Some(':') => parser.position += 1,
+            _ => return Err(parser.error(&format!("期望 = 或 : ({})", key))),
+        }
+        let raw = parser.scan_expression(Context::Object)?;
+        map.insert(key, literal(&raw)?);
+    }
+}
+
+fn insert_block(body: &mut Map<String, Value>, name: &str, labels: &[String], block: Map<String, Value>) -> Result<()> {
+    let mut slot = body.entry(name.to_string()).or_insert(Value::Null);
+    for label in labels {
+        if slot.is_null() {
+            *slot = Value::Object(Map::new());
+        }
+        slot = slot
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("块 {} 与同名属性冲突", name))?
+            .entry(label.clone())
+            .or_insert(Value::Null);
+    }
+
+    match slot {
+        Value::Null => *slot = Value::Object(block),
+        Value::Array(arr) => arr.push(Value::Object(block)),
+        other => {
+            let previous = other.take();
+            *other = Value::Array(vec![previous, Value::Object(block)]);
+        }
+    }
+    Ok(())
+}
+
+pub fn parse_hcl(input: &str) -> Result<HashMap<String, Value>> {
+    let mut parser = Parser::new(input);
+    let body = parser.parse_body(true)?;
+    Ok(body.into_iter().collect())
+}
@@ -0,0 +1,397 @@
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+// JSON5 / JSONC 解析器：支持注释、尾随逗号、无引号键、单引号字符串和十六进制数字。
+// 注释按其后紧跟的键路径（以 . 连接，数组下标为数字）收集，便于输出时写回。
+struct Parser {
+    input: Vec<char>,
+    position: usize,
+    pending: Vec<String>,
+    comments: HashMap<String, String>,
+}
+
+impl Parser {
+    fn new(input: &str) -> Self {
+        Parser {
+            input: input.chars().collect(),
+            position: 0,
+            pending: Vec::new(),
+            comments: HashMap::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input.get(self.position).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.input.get(self.position + offset).copied()
+    }
+
+    fn error(&self, message: &str) -> anyhow::Error {
+        let consumed = &self.input[..self.position.min(self.input.len())];
+        let line = consumed.iter().filter(|&&c| c == '\n').count() + 1;
+        let column = consumed.iter().rev().take_while(|&&c| c != '\n').count() + 1;
+        anyhow!("JSON5 解析错误: {} (第 {} 行第 {} 列)", message, line, column)
+    }
+
+    fn parse(&mut self) -> Result<Value> {
+        self.skip_whitespace()?;
+        let value = self.parse_value("")?;
+        self.skip_whitespace()?;
+        if self.peek().is_some() {
+            return Err(self.error("解析未完成"));
+        }
+        Ok(value)
+    }
+
+    fn skip_whitespace(&mut self) -> Result<()> {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || c == '\u{feff}' {
+                self.position += 1;
+            } else if c == '/' && matches!(self.peek_at(1), Some('/') | Some('*')) {
+                let comment = self.read_comment()?;
+                self.pending.push(comment);
+            } else {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_comment(&mut self) -> Result<String> {
+        self.position += 1;
+        let kind = self.peek();
+        self.position += 1;
+
+        let mut text = String::new();
+        if kind == Some('/') {
+            while let Some(c) = self.peek() {
+                if c == '\n' {
+                    break;
+                }
+                text.push(c);
+                self.position += 1;
+            }
+        } else {
+            loop {
+                match self.peek() {
+                    Some('*') if self.peek_at(1) == Some('/') => {
+                        self.position += 2;
+                        break;
+                    }
+                    Some(c) => {
+                        text.push(c);
+                        self.position += 1;
+                    }
+                    None => return Err(self.error("未闭合的注释")),
+                }
+            }
+        }
+
+        let lines: Vec<_> = text
+            .lines()
+            .map(|l| l.trim().trim_start_matches('*').trim())
+            .filter(|l| !l.is_empty())
+            .collect();
+        Ok(lines.join("\n"))
+    }
+
+    // 值后面同一行的注释属于该值
+    fn take_trailing_comment(&mut self, path: &str) -> Result<()> {
+        let start = self.position;
+        while matches!(self.peek(), Some(' ') | Some('\t')) {
+            self.position += 1;
+        }
+        if self.peek() == Some('/') && self.peek_at(1) == Some('/') {
+            let comment = self.read_comment()?;
+            self.pending.push(comment);
+            self.attach_comments(path);
+        } else {
+            self.position = start;
+        }
+        Ok(())
+    }
+
+    fn attach_comments(&mut self, path: &str) {
+        let pending: Vec<_> = self.pending.drain(..).filter(|c| !c.is_empty()).collect();
+        if pending.is_empty() {
+            return;
+        }
+        let entry = self.comments.entry(path.to_string()).or_default();
+        for comment in pending {
+            if !entry.is_empty() {
+                entry.push('\n');
+            }
+            entry.push_str(&comment);
+        }
+    }
+
+    fn parse_value(&mut self, path: &str) -> Result<Value> {
+        match self.peek() {
+            Some('{') => self.parse_object(path),
+            Some('[') => self.parse_array(path),
+            Some('"') | Some('\'') => Ok(Value::String(self.read_string()?)),
+            Some(c) if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => self.read_number(),
+            Some(c) if is_identifier_start(c) => {
+                let word = self.read_identifier();
+                match word.as_str() {
+                    "true" => Ok(Value::Bool(true)),
+                    "false" => Ok(Value::Bool(false)),
+                    "null" => Ok(Value::Null),
+                    "Infinity" | "NaN" => Err(self.error(&format!("不支持的数值: {}", word))),
+                    _ => Err(self.error(&format!("无效的值: {}", word))),
+                }
+            }
+            Some(c) => Err(self.error(&format!("无效的字符: {}", c))),
+            None => Err(self.error("意外的文件结尾")),
+        }
+    }
+
+    fn parse_object(&mut self, path: &str) -> Result<Value> {
+        let mut map = Map::new();
+        self.position += 1; // 跳过 {
+
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some('}') {
+                self.position += 1;
+                break;
+            }
+
+            let key = match self.peek() {
+                Some('"') | Some('\'') => self.read_string()?,
+                Some(c) if is_identifier_start(c) => self.read_identifier(),
+                _ => return Err(self.error("对象键必须是字符串或标识符")),
+            };
+            let child = join_path(path, &key);
+            self.attach_comments(&child);
+
+            self.skip_whitespace()?;
+            if self.peek() != Some(':') {
+                return Err(self.error("期望 :"));
+            }
+            self.position += 1;
+            self.skip_whitespace()?;
+
+            let value = self.parse_value(&child)?;
+            map.insert(key, value);
+
+            self.take_trailing_comment(&child)?;
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(',') => {
+                    self.position += 1;
+                    self.take_trailing_comment(&child)?;
+                }
+                Some('}') => {}
+                _ => return Err(self.error("期望 , 或 }")),
+            }
+        }
+
+        // 对象末尾、没有后续键的注释直接丢弃
+        self.pending.clear();
+        Ok(Value::Object(map))
+    }
+
+    fn parse_array(&mut self, path: &str) -> Result<Value> {
+        let mut array = Vec::new();
+        self.position += 1; // 跳过 [
+
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some(']') {
+                self.position += 1;
+                break;
+            }
+
+            let child = join_path(path, &array.len().to_string());
+            self.attach_comments(&child);
+            let value = self.parse_value(&child)?;
+            array.push(value);
+
+            self.take_trailing_comment(&child)?;
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(',') => {
+                    self.position += 1;
+                    self.take_trailing_comment(&child)?;
+                }
+                Some(']') => {}
+                _ => return Err(self.error("期望 , 或 ]")),
+            }
+        }
+
+        self.pending.clear();
+        Ok(Value::Array(array))
+    }
+
+    fn read_identifier(&mut self) -> String {
+        let mut result = String::new();
+        while let Some(c) = self.peek() {
+            if is_identifier_start(c) || c.is_ascii_digit() {
+                result.push(c);
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+        result
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        let quote = self.peek().unwrap_or('"');
+        self.position += 1;
+
+        let mut result = String::new();
+        loop {
+            let c = self.peek().ok_or_else(|| self.error("未闭合的字符串"))?;
+            self.position += 1;
+            if c == quote {
+                return Ok(result);
+            }
+            if c == '\n' {
+                return Err(self.error("字符串中不能直接换行"));
+            }
+            if c != '\\' {
+                result.push(c);
+                continue;
+            }
+
+            let escaped = self.peek().ok_or_else(|| self.error("未闭合的字符串"))?;
+            self.position += 1;
+            match escaped {
+                'n' => result.push('\n'),
+                't' => result.push('\t'),
+                'r' => result.push('\r'),
+                'b' => result.push('\u{8}'),
+                'f' => result.push('\u{c}'),
+                'v' => result.push('\u{b}'),
+                '0' => result.push('\0'),
+                // 行尾的反斜杠表示续行
+                '\n' => {}
+                '\r' => {
+                    if self.peek() == Some('\n') {
+                        self.position += 1;
+                    }
+                }
+                'x' => {
+                    let code = self.read_hex_digits(2)?;
+                    result.push(char::from_u32(code).ok_or_else(|| self.error("无效的转义"))?);
+                }
+                'u' => {
+                    let mut code = self.read_hex_digits(4)?;
+                    // UTF-16 代理对
+                    if (0xD800..0xDC00).contains(&code) && self.peek() == Some('\\') && self.peek_at(1) == Some('u') {
+                        self.position += 2;
+                        let low = self.read_hex_digits(4)?;
+                        code = 0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+                    }
+                    result.push(char::from_u32(code).ok_or_else(|| self.error("无效的 Unicode 转义"))?);
+                }
+                other => result.push(other),
+            }
+        }
+    }
+
+    fn read_hex_digits(&mut self, count: usize) -> Result<u32> {
+        let mut code = 0;
+        for _ in 0..count {
+            let digit = self
+                .peek()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| self.error("无效的十六进制转义"))?;
+            code = code * 16 + digit;
+            self.position += 1;
+        }
+        Ok(code)
+    }
+
+    fn read_number(&mut self) -> Result<Value> {
+        let mut negative = false;
+        match self.peek() {
+            Some('-') => {
+                negative = true;
+                self.position += 1;
+            }
+            Some('+') => self.position += 1,
+            _ => {}
+        }
+
+        if self.peek() == Some('0') && matches!(self.peek_at(1), Some('x') | Some('X')) {
+            self.position += 2;
+            let mut digits = String::new();
+            while let Some(c) = self.peek().filter(|c| c.is_ascii_hexdigit()) {
+                digits.push(c);
+                self.position += 1;
+            }
+            let n = u64::from_str_radix(&digits, 16).map_err(|_| self.error(&format!("无效的十六进制数字: 0x{}", digits)))?;
+            if !negative {
+                return Ok(Value::Number(Number::from(n)));
+            }
+            let n = 0i64.checked_sub_unsigned(n).ok_or_else(|| self.error(&format!("十六进制数字超出范围: -0x{}", digits)))?;
+            return Ok(Value::Number(Number::from(n)));
+        }
+
+        if self.peek().is_some_and(is_identifier_start) {
+            let word = self.read_identifier();
+            return Err(self.error(&format!("不支持的数值: {}", word)));
+        }
+
+        let mut text = String::new();
+        if negative {
+            text.push('-');
+        }
+        while let Some(c) = self.peek() {
+            if c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-') {
+                text.push(c);
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+
+        // JSON5 允许 .5 和 5. 这样的写法
+        let normalized = text.replace("-.", "-0.");
+        let normalized = if normalized.starts_with('.') { format!("0{}", normalized) } else { normalized };
+        let normalized = normalized.replace(".e", ".0e").replace(".E", ".0E");
+        let normalized = normalized.strip_suffix('.').map(|s| format!("{}.0", s)).unwrap_or(normalized);
+
+        if let Ok(n) = normalized.parse::<i64>() {
+            return Ok(Value::Number(Number::from(n)));
+        }
+        // 超出 i64 的正整数按 u64 保存，避免转成浮点数丢失精度
+        if let Ok(n) = normalized.parse::<u64>() {
+            return Ok(Value::Number(Number::from(n)));
+        }
+        normalized
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| self.error(&format!("无效的数字: {}", text)))
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+fn join_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+pub fn parse_json5(input: &str) -> Result<(HashMap<String, Value>, HashMap<String, String>)> {
+    let mut parser = Parser::new(input);
+    let value = parser.parse()?;
+
+    match value {
+        Value::Object(map) => Ok((map.into_iter().collect(), parser.comments)),
+        _ => Err(anyhow!("JSON5 根节点必须是对象")),
+    }
+}
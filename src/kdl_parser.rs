@@ -0,0 +1,533 @@
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+// KDL 解析器（兼容 v1 和 v2 语法）。节点与 JSON 的映射规则：
+// - 文档和子节点块映射为对象，键为节点名；同名节点重复出现时合并为数组
+// - 只有一个参数的节点映射为该参数，`port 8080` -> "port": 8080
+// - 有多个参数的节点映射为数组，`hosts "a" "b"` -> "hosts": ["a", "b"]
+// - 子节点全部名为 `-` 的节点映射为数组，每个 `-` 节点是一个元素
+// - 有属性或子节点块的节点映射为对象，属性和子节点都是对象的键；此时的参数放在 "$args" 数组中
+// - 没有参数、属性和子节点块的节点映射为 null
+// - 带类型注解的值 `(u8)1` 映射为 {"$type": "u8", "$value": 1}，节点的类型注解放在 "$type" 键中
+// - 类型注解为 `$array` 的节点按数组解析，`($array)list {}` -> "list": []，用于区分空数组和空对象
+pub const ARGS_KEY: &str = "$args";
+pub const TYPE_KEY: &str = "$type";
+pub const VALUE_KEY: &str = "$value";
+pub const ARRAY_TYPE: &str = "$array";
+
+struct Node {
+    name: String,
+    ty: Option<String>,
+    args: Vec<Value>,
+    props: Map<String, Value>,
+    children: Option<Vec<Node>>,
+}
+
+struct Parser {
+    input: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Self {
+        Parser {
+            input: input.chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input.get(self.position).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.input.get(self.position + offset).copied()
+    }
+
+    fn error(&self, message: &str) -> anyhow::Error {
+        let consumed = &self.input[..self.position.min(self.input.len())];
+        let line = consumed.iter().filter(|&&c| c == '\n').count() + 1;
+        anyhow!("KDL 解析错误: {} (第 {} 行)", message, line)
+    }
+
+    fn skip_block_comment(&mut self) -> Result<()> {
+        self.position += 2;
+        let mut depth = 1;
+        while depth > 0 {
+            match (self.peek(), self.peek_at(1)) {
+                (Some('/'), Some('*')) => {
+                    depth += 1;
+                    self.position += 2;
+                }
+                (Some('*'), Some('/')) => {
+                    depth -= 1;
+                    self.position += 2;
+                }
+                (Some(_), _) => self.position += 1,
+                (None, _) => return Err(self.error("未闭合的注释")),
+            }
+        }
+        Ok(())
+    }
+
+    fn skip_line_comment(&mut self) {
+        while self.peek().is_some_and(|c| c != '\n') {
+            self.position += 1;
+        }
+    }
+
+    // 跳过节点内部的空白、块注释和续行符，不跨越换行
+    fn skip_node_space(&mut self) -> Result<()> {
+        loop {
+            match self.peek() {
+                Some(c) if c != '\n' && c.is_whitespace() => self.position += 1,
+                Some('/') if self.peek_at(1) == Some('*') => self.skip_block_comment()?,
+                Some('\\') => {
+                    self.position += 1;
+                    while self.peek().is_some_and(|c| c != '\n' && c.is_whitespace()) {
+                        self.position += 1;
+                    }
+                    if self.peek() == Some('/') && self.peek_at(1) == Some('/') {
+                        self.skip_line_comment();
+                    }
+                    if self.peek() == Some('\n') {
+                        self.position += 1;
+                    } else if self.peek().is_some() {
+                        return Err(self.error("续行符后必须换行"));
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    // 跳过节点之间的空白、换行、分号和注释
+    fn skip_line_space(&mut self) -> Result<()> {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() || c == ';' || c == '\u{feff}' => self.position += 1,
+                Some('/') if self.peek_at(1) == Some('/') => self.skip_line_comment(),
+                Some('/') if self.peek_at(1) == Some('*') => self.skip_block_comment()?,
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn parse_nodes(&mut self, nested: bool) -> Result<Vec<Node>> {
+        let mut nodes = Vec::new();
+        loop {
+            self.skip_line_space()?;
+            match self.peek() {
+                None if !nested => return Ok(nodes),
+                None => return Err(self.error("缺少 }")),
+                Some('}') if nested => {
+                    self.position += 1;
+                    return Ok(nodes);
+                }
+                _ => {}
+            }
+
+            let discarded = self.take_slashdash()?;
+            let node = self.parse_node()?;
+            if !discarded {
+                nodes.push(node);
+            }
+        }
+    }
+
+    fn take_slashdash(&mut self) -> Result<bool> {
+        if self.peek() == Some('/') && self.peek_at(1) == Some('-') {
+            self.position += 2;
+            self.skip_line_space()?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn parse_node(&mut self) -> Result<Node> {
+        let ty = self.parse_type()?;
+        let name = self.parse_string_or_identifier()?;
+        let mut node = Node {
+            name,
+            ty,
+            args: Vec::new(),
+            props: Map::new(),
+            children: None,
+        };
+
+        loop {
+            self.skip_node_space()?;
+            let discarded = if self.peek() == Some('/') && self.peek_at(1) == Some('-') {
+                self.position += 2;
+                self.skip_node_space()?;
+                true
+            } else {
+                false
+            };
+
+            match self.peek() {
+                None | Some('\n') | Some(';') => break,
+                Some('/') if self.peek_at(1) == Some('/') => break,
+                Some('}') => break,
+                Some('{') => {
+                    self.position += 1;
+                    let children = self.parse_nodes(true)?;
+                    if !discarded {
+                        node.children = Some(children);
+                    }
+                }
+                _ => {
+                    let (key, value) = self.parse_entry()?;
+                    if discarded {
+                        continue;
+                    }
+                    match key {
+                        Some(key) => {
+                            node.props.insert(key, value);
+                        }
+                        None => node.args.push(value),
+                    }
+                }
+            }
+        }
+
+        Ok(node)
+    }
+
+    fn parse_type(&mut self) -> Result<Option<String>> {
+        if self.peek() != Some('(') {
+            return Ok(None);
+        }
+        self.position += 1;
+        let ty = self.parse_string_or_identifier()?;
+        if self.peek() != Some(')') {
+            return Err(self.error("类型注解缺少 )"));
+        }
+        self.position += 1;
+        Ok(Some(ty))
+    }
+
+    // 参数或 key=value 属性
+    fn parse_entry(&mut self) -> Result<(Option<String>, Value)> {
+        let start = self.position;
+        if self.peek() != Some('(') {
+            if let Ok(Token::Text(key)) = self.parse_token() {
+                if self.peek() == Some('=') {
+                    self.position += 1;
+                    return Ok((Some(key), self.parse_value()?));
+                }
+            }
+            self.position = start;
+        }
+        Ok((None, self.parse_value()?))
+    }
+
+    fn parse_value(&mut self) -> Result<Value> {
+        let ty = self.parse_type()?;
+        let value = match self.parse_token()? {
+            Token::Text(s) => Value::String(s),
+            Token::Value(v) => v,
+        };
+        Ok(match ty {
+            Some(ty) => {
+                let mut annotated = Map::new();
+                annotated.insert(TYPE_KEY.to_string(), Value::String(ty));
+                annotated.insert(VALUE_KEY.to_string(), value);
+                Value::Object(annotated)
+            }
+            None => value,
+        })
+    }
+
+    fn parse_string_or_identifier(&mut self) -> Result<String> {
+        match self.parse_token()? {
+            Token::Text(s) => Ok(s),
+            Token::Value(v) => Err(self.error(&format!("期望名称，得到 {}", v))),
+        }
+    }
+
+    fn parse_token(&mut self) -> Result<Token> {
+        match self.peek() {
+            Some('"') => Ok(Token::Text(self.read_string()?)),
+            Some('r') if matches!(self.peek_at(1), Some('"') | Some('#')) => {
+                self.position += 1;
+                Ok(Token::Text(self.read_raw_string()?))
+            }
+            Some('#') if matches!(self.peek_at(1), Some('"') | Some('#')) => Ok(Token::Text(self.read_raw_string()?)),
+            Some('#') => {
+                self.position += 1;
+                let word = self.read_identifier();
+                match word.as_str() {
+                    "true" => Ok(Token::Value(Value::Bool(true))),
+                    "false" => Ok(Token::Value(Value::Bool(false))),
+                    "null" => Ok(Token::Value(Value::Null)),
+                    _ => Err(self.error(&format!("不支持的关键字: #{}", word))),
+                }
+            }
+            Some(c) if c.is_ascii_digit() || (matches!(c, '-' | '+') && self.peek_at(1).is_some_and(|c| c.is_ascii_digit())) => {
+                Ok(Token::Value(self.read_number()?))
+            }
+            Some(c) if is_identifier_char(c) => {
+                let word = self.read_identifier();
+                Ok(match word.as_str() {
+                    "true" => Token::Value(Value::Bool(true)),
+                    "false" => Token::Value(Value::Bool(false)),
+                    "null" => Token::Value(Value::Null),
+                    _ => Token::Text(word),
+                })
+            }
+            Some(c) => Err(self.error(&format!("无效的字符: {}", c))),
+            None => Err(self.error("意外的文件结尾")),
+        }
+    }
+
+    fn read_identifier(&mut self) -> String {
+        let mut result = String::new();
+        while let Some(c) = self.peek().filter(|&c| is_identifier_char(c)) {
+            result.push(c);
+            self.position += 1;
+        }
+        result
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        // v2 的多行字符串 """ ... """，去掉与结束行相同的缩进
+        if self.peek_at(1) == Some('"') && self.peek_at(2) == Some('"') {
+            self.position += 3;
+            let mut raw = String::new();
+            loop {
+                if self.peek() == Some('"') && self.peek_at(1) == Some('"') && self.peek_at(2) == Some('"') {
+                    self.position += 3;
+                    break;
+                }
+                let c = self.peek().ok_or_else(|| self.error("未闭合的字符串"))?;
+                self.position += 1;
+                if c == '\\' {
+                    raw.push(self.read_escape()?);
+                } else {
+                    raw.push(c);
+                }
+            }
+            return Ok(dedent(&raw));
+        }
+
+        self.position += 1;
+        let mut result = String::new();
+        loop {
+            let c = self.peek().ok_or_else(|| self.error("未闭合的字符串"))?;
+            self.position += 1;
+            match c {
+                '"' => return Ok(result),
+                '\\' => {
+                    // 反斜杠后跟空白（含换行）时整段空白被忽略
+                    if self.peek().is_some_and(char::is_whitespace) {
+                        while self.peek().is_some_and(char::is_whitespace) {
+                            self.position += 1;
+                        }
+                    } else {
+                        result.push(self.read_escape()?);
+                    }
+                }
+                c => result.push(c),
+            }
+        }
+    }
+
+    fn read_escape(&mut self) -> Result<char> {
+        let c = self.peek().ok_or_else(|| self.error("未闭合的字符串"))?;
+        self.position += 1;
+        Ok(match c {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            's' => ' ',
+            'u' => {
+                if self.peek() != Some('{') {
+                    return Err(self.error("无效的 Unicode 转义"));
+                }
+                self.position += 1;
+                let mut hex = String::new();
+                while let Some(c) = self.peek() {
+                    self.position += 1;
+                    if c == '}' {
+                        break;
+                    }
+                    hex.push(c);
+                }
+                let code = u32::from_str_radix(&hex, 16).map_err(|_| self.error("无效的 Unicode 转义"))?;
+                char::from_u32(code).ok_or_else(|| self.error("无效的 Unicode 转义"))?
+            }
+            other => other,
+        })
+    }
+
+    fn read_raw_string(&mut self) -> Result<String> {
+        let mut hashes = 0;
+        while self.peek() == Some('#') {
+            hashes += 1;
+            self.position += 1;
+        }
+        if self.peek() != Some('"') {
+            return Err(self.error("无效的原始字符串"));
+        }
+        let multiline = self.peek_at(1) == Some('"') && self.peek_at(2) == Some('"');
+        let quotes = if multiline { 3 } else { 1 };
+        self.position += quotes;
+
+        let mut result = String::new();
+        loop {
+            let c = self.peek().ok_or_else(|| self.error("未闭合的原始字符串"))?;
+            if (0..quotes).all(|i| self.peek_at(i) == Some('"')) && (0..hashes).all(|i| self.peek_at(quotes + i) == Some('#')) {
+                self.position += quotes + hashes;
+                return Ok(if multiline { dedent(&result) } else { result });
+            }
+            result.push(c);
+            self.position += 1;
+        }
+    }
+
+    fn read_number(&mut self) -> Result<Value> {
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | '_') {
+                if c != '_' {
+                    text.push(c);
+                }
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(&text)),
+        };
+        for (prefix, radix) in [("0x", 16), ("0b", 2), ("0o", 8)] {
+            if let Some(body) = digits.strip_prefix(prefix) {
+                let n = i64::from_str_radix(body, radix).map_err(|_| self.error(&format!("无效的数字: {}", text)))?;
+                return Ok(Value::Number(Number::from(if negative { -n } else { n })));
+            }
+        }
+
+        let signed = if negative { format!("-{}", digits) } else { digits.to_string() };
+        if let Ok(n) = signed.parse::<i64>() {
+            return Ok(Value::Number(Number::from(n)));
+        }
+        signed
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| self.error(&format!("无效的数字: {}", text)))
+    }
+}
+
+enum Token {
+    Text(String),
+    Value(Value),
+}
+
+pub fn is_identifier_char(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '\\' | '/' | '(' | ')' | '{' | '}' | '<' | '>' | ';' | '[' | ']' | '=' | ',' | '"' | '#')
+}
+
+fn dedent(raw: &str) -> String {
+    let raw = raw.strip_prefix('\n').unwrap_or(raw);
+    let mut lines: Vec<&str> = raw.split('\n').collect();
+    let indent = match lines.last() {
+        Some(last) if last.trim().is_empty() => {
+            let indent = last.to_string();
+            lines.pop();
+            indent
+        }
+        _ => String::new(),
+    };
+    lines
+        .iter()
+        .map(|l| l.strip_prefix(indent.as_str()).unwrap_or(l.trim_start()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn node_value(node: Node) -> Value {
+    let is_array = node.ty.as_deref() == Some(ARRAY_TYPE)
+        && node.args.is_empty()
+        && node.props.is_empty()
+        && node.children.iter().flatten().all(|c| c.name == "-");
+    if is_array {
+        return Value::Array(node.children.unwrap_or_default().into_iter().map(node_value).collect());
+    }
+
+    if let Some(children) = &node.children {
+        if !children.is_empty() && children.iter().all(|c| c.name == "-") && node.args.is_empty() && node.props.is_empty() {
+            let items = node.children.unwrap_or_default().into_iter().map(node_value).collect();
+            return with_type(Value::Array(items), node.ty);
+        }
+    }
+
+    if node.children.is_none() && node.props.is_empty() && node.ty.is_none() {
+        let mut args = node.args;
+        return match args.len() {
+            0 => Value::Null,
+            1 => args.remove(0),
+            _ => Value::Array(args),
+        };
+    }
+
+    let mut obj = Map::new();
+    if let Some(ty) = node.ty {
+        obj.insert(TYPE_KEY.to_string(), Value::String(ty));
+    }
+    if !node.args.is_empty() {
+        obj.insert(ARGS_KEY.to_string(), Value::Array(node.args));
+    }
+    obj.extend(node.props);
+    for (key, value) in nodes_to_map(node.children.unwrap_or_default()) {
+        obj.insert(key, value);
+    }
+    Value::Object(obj)
+}
+
+fn with_type(value: Value, ty: Option<String>) -> Value {
+    match ty {
+        Some(ty) => {
+            let mut annotated = Map::new();
+            annotated.insert(TYPE_KEY.to_string(), Value::String(ty));
+            annotated.insert(VALUE_KEY.to_string(), value);
+            Value::Object(annotated)
+        }
+        None => value,
+    }
+}
+
+fn nodes_to_map(nodes: Vec<Node>) -> Map<String, Value> {
+    let mut map = Map::new();
+    let mut repeated: HashMap<String, usize> = HashMap::new();
+    for node in nodes {
+        let name = node.name.clone();
+        let value = node_value(node);
+        let count = repeated.entry(name.clone()).or_insert(0);
+        *count += 1;
+        match (map.get_mut(&name), *count) {
+            (Some(Value::Array(items)), n) if n > 2 => items.push(value),
+            (Some(existing), _) => {
+                let first = existing.take();
+                *existing = Value::Array(vec![first, value]);
+            }
+            (None, _) => {
+                map.insert(name, value);
+            }
+        }
+    }
+    map
+}
+
+pub fn parse_kdl(input: &str) -> Result<HashMap<String, Value>> {
+    let mut parser = Parser::new(input);
+    let nodes = parser.parse_nodes(false)?;
+    Ok(nodes_to_map(nodes).into_iter().collect())
+}
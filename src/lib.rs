@@ -3,8 +3,29 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
+mod canonical_json;
+mod config_diff;
+mod config_merge;
+mod config_path;
+mod config_query;
 mod converters;
+mod format_detect;
+mod gitconfig_parser;
+mod hcl_parser;
 mod hocon_parser;
+mod json5_parser;
+mod kdl_parser;
+mod properties_parser;
+mod ron_parser;
+mod systemd_parser;
+mod toml_editor;
+mod toml_writer;
+
+pub use canonical_json::to_canonical_json;
+pub use config_diff::{diff_configs, ConfigChange, DiffOptions};
+pub use config_merge::{merge_configs, ArrayMergeStrategy, MergeOptions, MergedConfig};
+pub use config_path::{format_path, remove_path, set_path, PathSegment};
+pub use config_query::{parse_query, Query};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ConfigFormat {
@@ -13,8 +34,23 @@ pub enum ConfigFormat {
     Hocon,
     Env,
     Json,
+    CanonicalJson,
+    Json5,
     Yaml,
     Toml,
+    Properties,
+    Plist,
+    Hcl,
+    Ron,
+    Kdl,
+    MessagePack,
+    Cbor,
+    GitConfig,
+    Systemd,
+    EditorConfig,
+    DesktopEntry,
+    Csv,
+    Tsv,
 }
 
 impl ConfigFormat {
@@ -25,8 +61,23 @@ impl ConfigFormat {
             "conf" | "hocon" => Some(ConfigFormat::Hocon),
             "env" => Some(ConfigFormat::Env),
             "json" => Some(ConfigFormat::Json),
+            "jcs" => Some(ConfigFormat::CanonicalJson),
+            "json5" | "jsonc" => Some(ConfigFormat::Json5),
             "yaml" | "yml" => Some(ConfigFormat::Yaml),
             "toml" => Some(ConfigFormat::Toml),
+            "properties" => Some(ConfigFormat::Properties),
+            "plist" => Some(ConfigFormat::Plist),
+            "hcl" | "tf" | "tfvars" => Some(ConfigFormat::Hcl),
+            "ron" => Some(ConfigFormat::Ron),
+            "kdl" => Some(ConfigFormat::Kdl),
+            "msgpack" | "mpk" => Some(ConfigFormat::MessagePack),
+            "cbor" => Some(ConfigFormat::Cbor),
+            "gitconfig" | "gitmodules" => Some(ConfigFormat::GitConfig),
+            "service" | "socket" | "timer" | "mount" => Some(ConfigFormat::Systemd),
+            "editorconfig" => Some(ConfigFormat::EditorConfig),
+            "desktop" => Some(ConfigFormat::DesktopEntry),
+            "csv" => Some(ConfigFormat::Csv),
+            "tsv" | "tab" => Some(ConfigFormat::Tsv),
             _ => None,
         }
     }
@@ -38,10 +89,63 @@ impl ConfigFormat {
             ConfigFormat::Hocon => "conf",
             ConfigFormat::Env => "env",
             ConfigFormat::Json => "json",
+            ConfigFormat::CanonicalJson => "jcs",
+            ConfigFormat::Json5 => "json5",
             ConfigFormat::Yaml => "yaml",
             ConfigFormat::Toml => "toml",
+            ConfigFormat::Properties => "properties",
+            ConfigFormat::Plist => "plist",
+            ConfigFormat::Hcl => "hcl",
+            ConfigFormat::Ron => "ron",
+            ConfigFormat::Kdl => "kdl",
+            ConfigFormat::MessagePack => "msgpack",
+            ConfigFormat::Cbor => "cbor",
+            ConfigFormat::GitConfig => "gitconfig",
+            ConfigFormat::Systemd => "service",
+            ConfigFormat::EditorConfig => "editorconfig",
+            ConfigFormat::DesktopEntry => "desktop",
+            ConfigFormat::Csv => "csv",
+            ConfigFormat::Tsv => "tsv",
         }
     }
+
+    // 根据内容嗅探格式，返回按得分从高到低排列的候选
+    pub fn detect_candidates(content: &str) -> Vec<(Self, u32)> {
+        format_detect::score_formats(content)
+    }
+
+    // 根据内容嗅探格式；没有候选或最高分并列时返回错误并列出候选
+    pub fn detect(content: &str) -> Result<Self> {
+        let candidates = Self::detect_candidates(content);
+        match candidates.as_slice() {
+            [] => Err(anyhow::anyhow!("无法从内容判断格式")),
+            [(first, top), rest @ ..] => {
+                let tied: Vec<_> = rest.iter().filter(|(_, score)| score == top).map(|(f, _)| f.to_extension()).collect();
+                if tied.is_empty() {
+                    Ok(*first)
+                } else {
+                    Err(anyhow::anyhow!(
+                        "无法从内容判断格式，以下格式同样可能: {}, {}",
+                        first.to_extension(),
+                        tied.join(", ")
+                    ))
+                }
+            }
+        }
+    }
+
+    // 先按扩展名识别；.gitconfig 这类以点开头的文件没有扩展名，按去掉点的文件名识别
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_extension)
+            .or_else(|| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_prefix('.'))
+                    .and_then(Self::from_extension)
+            })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,23 +154,203 @@ pub struct ConfigValue {
     pub value: HashMap<String, serde_json::Value>,
 }
 
-pub trait ConfigConverter {
+// 转换器只持有不可变的选项，要求 Send + Sync 以便在批量转换的工作线程之间共享
+pub trait ConfigConverter: Send + Sync {
     fn parse(&self, content: &str) -> Result<ConfigValue>;
     fn format(&self, config: &ConfigValue) -> Result<String>;
+
+    // 按字节读写：文本格式默认按 UTF-8 编解码，二进制格式（plist、MessagePack、CBOR）需要覆盖
+    fn parse_bytes(&self, bytes: &[u8]) -> Result<ConfigValue> {
+        self.parse(std::str::from_utf8(bytes)?)
+    }
+
+    fn format_bytes(&self, config: &ConfigValue) -> Result<Vec<u8>> {
+        Ok(self.format(config)?.into_bytes())
+    }
+}
+
+// 保留格式的编辑接口：直接在原文上修改，只改动被触及的行，注释、顺序和内联表保持不变
+pub trait ConfigEditor {
+    fn set(&self, content: &str, path: &[PathSegment], value: &Value) -> Result<String>;
+    fn remove(&self, content: &str, path: &[PathSegment]) -> Result<String>;
+}
+
+// TOML 输出排版选项
+#[derive(Debug, Clone)]
+pub struct TomlOptions {
+    // 嵌套对象写成内联表 `a = { b = 1 }` 而不是 `[a]` 标准表
+    pub inline_tables: bool,
+    // 对象数组写成 `[[a]]` 表数组；为 false 时写成内联数组
+    pub array_of_tables: bool,
+    // 内联表、数组的最大宽度，超出时内联表改用标准表、数组改为每行一个元素
+    pub max_inline_width: Option<usize>,
+}
+
+impl Default for TomlOptions {
+    fn default() -> Self {
+        TomlOptions {
+            inline_tables: false,
+            array_of_tables: true,
+            max_inline_width: Some(80),
+        }
+    }
+}
+
+// JSON 缩进方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonIndent {
+    Spaces(usize),
+    Tab,
+}
+
+impl std::str::FromStr for JsonIndent {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "tab" | "tabs" => Ok(JsonIndent::Tab),
+            n => n
+                .parse::<usize>()
+                .map(JsonIndent::Spaces)
+                .map_err(|_| anyhow::anyhow!("无效的缩进: {}，应为空格数或 tab", s)),
+        }
+    }
+}
+
+// JSON 输出风格选项
+#[derive(Debug, Clone)]
+pub struct JsonOptions {
+    // 紧凑输出，不换行不缩进
+    pub compact: bool,
+    pub indent: JsonIndent,
+    // 将非 ASCII 字符转义为 \uXXXX
+    pub ascii: bool,
+    // 在文件末尾追加换行
+    pub trailing_newline: bool,
+    // 按 RFC 8785 输出规范化 JSON，启用后忽略其余选项
+    pub canonical: bool,
+}
+
+impl Default for JsonOptions {
+    fn default() -> Self {
+        JsonOptions {
+            compact: false,
+            indent: JsonIndent::Spaces(2),
+            ascii: false,
+            trailing_newline: false,
+            canonical: false,
+        }
+    }
+}
+
+// Java properties 输出选项
+#[derive(Debug, Clone)]
+pub struct PropertiesOptions {
+    // 将非 ASCII 字符写成 \uXXXX，使输出同时兼容 ISO-8859-1 和 UTF-8 读取方
+    pub unicode_escape: bool,
+}
+
+impl Default for PropertiesOptions {
+    fn default() -> Self {
+        PropertiesOptions { unicode_escape: true }
+    }
+}
+
+// Apple plist 输出选项
+#[derive(Debug, Clone, Default)]
+pub struct PlistOptions {
+    // 输出二进制 bplist00 而不是 XML，需通过 format_bytes 获取
+    pub binary: bool,
+}
+
+// HCL 输出选项
+#[derive(Debug, Clone, Default)]
+pub struct HclOptions {
+    // 将 Terraform 块和对象数组写成块而不是对象表达式；resource、data、variable 等块按约定的标签层数展开，普通对象仍写成属性。
+    // 为 false 时只输出属性，适合 .tfvars
+    pub blocks: bool,
+}
+
+// CSV/TSV 选项
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    // 字段分隔符，TSV 为 \t
+    pub delimiter: u8,
+    // 解析时将整数、浮点数、true/false 转换为对应类型，空单元格转换为 null；为 false 时一律保留为字符串
+    pub infer_types: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: b',',
+            infer_types: false,
+        }
+    }
+}
+
+// 各格式的可选项
+#[derive(Debug, Clone, Default)]
+pub struct FormatOptions {
+    pub json: JsonOptions,
+    pub toml: TomlOptions,
+    pub properties: PropertiesOptions,
+    pub plist: PlistOptions,
+    pub hcl: HclOptions,
+    pub csv: CsvOptions,
 }
 
 pub struct ConfigConverterFactory;
 
 impl ConfigConverterFactory {
     pub fn get_converter(format: ConfigFormat) -> Box<dyn ConfigConverter> {
+        Self::get_converter_with_options(format, &FormatOptions::default())
+    }
+
+    pub fn get_converter_with_options(format: ConfigFormat, options: &FormatOptions) -> Box<dyn ConfigConverter> {
         match format {
             ConfigFormat::Ini => Box::new(IniConverter),
             ConfigFormat::Xml => Box::new(XmlConverter),
             ConfigFormat::Hocon => Box::new(HoconConverter),
             ConfigFormat::Env => Box::new(EnvConverter),
-            ConfigFormat::Json => Box::new(JsonConverter),
+            ConfigFormat::Json => Box::new(JsonConverter { options: options.json.clone() }),
+            ConfigFormat::CanonicalJson => Box::new(JsonConverter {
+                options: JsonOptions {
+                    canonical: true,
+                    ..options.json.clone()
+                },
+            }),
+            ConfigFormat::Json5 => Box::new(Json5Converter),
             ConfigFormat::Yaml => Box::new(YamlConverter),
-            ConfigFormat::Toml => Box::new(TomlConverter),
+            ConfigFormat::Toml => Box::new(TomlConverter { options: options.toml.clone() }),
+            ConfigFormat::Properties => Box::new(PropertiesConverter {
+                options: options.properties.clone(),
+            }),
+            ConfigFormat::Plist => Box::new(PlistConverter { options: options.plist.clone() }),
+            ConfigFormat::Hcl => Box::new(HclConverter { options: options.hcl.clone() }),
+            ConfigFormat::Ron => Box::new(RonConverter),
+            ConfigFormat::Kdl => Box::new(KdlConverter),
+            ConfigFormat::MessagePack => Box::new(MessagePackConverter),
+            ConfigFormat::Cbor => Box::new(CborConverter),
+            ConfigFormat::GitConfig => Box::new(GitConfigConverter),
+            ConfigFormat::Systemd => Box::new(SystemdConverter),
+            ConfigFormat::EditorConfig => Box::new(EditorConfigConverter),
+            ConfigFormat::DesktopEntry => Box::new(DesktopEntryConverter),
+            ConfigFormat::Csv => Box::new(CsvConverter { options: options.csv.clone() }),
+            ConfigFormat::Tsv => Box::new(CsvConverter {
+                options: CsvOptions {
+                    delimiter: b'\t',
+                    ..options.csv.clone()
+                },
+            }),
+        }
+    }
+
+    // 目前只有 TOML 支持保留格式的编辑
+    pub fn get_editor(format: ConfigFormat) -> Option<Box<dyn ConfigEditor>> {
+        match format {
+            ConfigFormat::Toml => Some(Box::new(TomlConverter::default())),
+            _ => None,
         }
     }
 }
@@ -76,9 +360,40 @@ pub struct IniConverter;
 pub struct XmlConverter;
 pub struct HoconConverter;
 pub struct EnvConverter;
-pub struct JsonConverter;
+#[derive(Default)]
+pub struct JsonConverter {
+    pub options: JsonOptions,
+}
+pub struct Json5Converter;
 pub struct YamlConverter;
-pub struct TomlConverter;
+#[derive(Default)]
+pub struct TomlConverter {
+    pub options: TomlOptions,
+}
+#[derive(Default)]
+pub struct PropertiesConverter {
+    pub options: PropertiesOptions,
+}
+#[derive(Default)]
+pub struct PlistConverter {
+    pub options: PlistOptions,
+}
+#[derive(Default)]
+pub struct HclConverter {
+    pub options: HclOptions,
+}
+pub struct RonConverter;
+pub struct KdlConverter;
+pub struct MessagePackConverter;
+pub struct CborConverter;
+pub struct GitConfigConverter;
+pub struct SystemdConverter;
+pub struct EditorConfigConverter;
+pub struct DesktopEntryConverter;
+#[derive(Default)]
+pub struct CsvConverter {
+    pub options: CsvOptions,
+}
 
 impl ConfigConverter for crate::HoconConverter {
     fn parse(&self, content: &str) -> Result<ConfigValue> {
@@ -96,8 +411,10 @@ impl ConfigConverter for crate::HoconConverter {
 
 fn format_hocon_value(output: &mut String, value: &HashMap<String, Value>, indent: usize) -> Result<()> {
     let indent_str = "  ".repeat(indent);
+    let mut entries: Vec<_> = value.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
     
-    for (key, value) in value {
+    for (key, value) in entries {
         output.push_str(&format!("{}{} = ", indent_str, key));
         
         match value {
@@ -1,17 +1,31 @@
 use anyhow::Result;
-use clap::Parser;
-use std::path::PathBuf;
+use clap::{Parser, Subcommand, ValueEnum};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
 
-use Fmto::{ConfigConverterFactory, ConfigFormat};
+use Fmto::{
+    diff_configs, merge_configs, parse_query, remove_path, set_path, ArrayMergeStrategy, ConfigChange, ConfigConverter, ConfigConverterFactory, ConfigFormat,
+    ConfigValue, CsvOptions, DiffOptions, FormatOptions, HclOptions, Json5Converter, JsonIndent, JsonOptions, MergeOptions, PlistOptions,
+    PathSegment, PropertiesOptions, TomlOptions,
+};
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Args {
-    /// 输入文件路径
-    #[arg(short = 'i', long)]
-    input: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// 输入文件路径，- 表示标准输入（默认）；也可以是目录或 glob 模式（如 'configs/**/*.yaml'），此时批量转换；
+    /// 指定多个输入文件时按顺序深度合并后输出
+    #[arg(short = 'i', long, num_args = 1.., default_value = "-")]
+    input: Vec<PathBuf>,
 
-    /// 输出文件路径（可选，可以指定多个）
+    /// 输出文件路径（可选，可以指定多个），- 表示标准输出
     #[arg(short = 'o', long, num_args = 1..)]
     output: Vec<PathBuf>,
 
@@ -26,51 +40,874 @@ struct Args {
     /// 输出文件格式（可选，可以指定多个，与输出文件一一对应）
     #[arg(short = 't', long, num_args = 1..)]
     output_format: Vec<String>,
+
+    /// JSON 输出：紧凑格式，不换行不缩进
+    #[arg(long)]
+    json_compact: bool,
+
+    /// JSON 输出：缩进空格数，或 tab 表示使用制表符
+    #[arg(long, default_value = "2")]
+    json_indent: JsonIndent,
+
+    /// JSON 输出：将非 ASCII 字符转义为 \uXXXX
+    #[arg(long)]
+    json_ascii: bool,
+
+    /// JSON 输出：在文件末尾追加换行
+    #[arg(long)]
+    json_trailing_newline: bool,
+
+    /// TOML 输出：嵌套对象写成内联表
+    #[arg(long)]
+    toml_inline_tables: bool,
+
+    /// TOML 输出：对象数组写成内联数组而不是 [[表数组]]
+    #[arg(long)]
+    toml_inline_arrays: bool,
+
+    /// TOML 输出：内联表和数组的最大宽度，超出时展开（0 表示不限制）
+    #[arg(long, default_value_t = 80)]
+    toml_max_width: usize,
+
+    /// properties 输出：直接写入 UTF-8 字符，不转义为 \uXXXX
+    #[arg(long)]
+    properties_utf8: bool,
+
+    /// plist 输出：写成二进制 bplist00 而不是 XML
+    #[arg(long)]
+    plist_binary: bool,
+
+    /// HCL 输出：将 Terraform 块（如 resource "type" "name" {}）和对象数组写成块，其他对象仍是属性；默认只输出属性
+    #[arg(long)]
+    hcl_blocks: bool,
+
+    /// CSV/TSV 输入：将数字、true/false 转换为对应类型，空单元格转换为 null
+    #[arg(long)]
+    csv_infer_types: bool,
+
+    /// 批量转换：只转换相对路径匹配这些 glob 模式的文件（默认转换所有能识别格式的文件）
+    #[arg(long, num_args = 1..)]
+    include: Vec<String>,
+
+    /// 批量转换：跳过相对路径匹配这些 glob 模式的文件
+    #[arg(long, num_args = 1..)]
+    exclude: Vec<String>,
+
+    /// 批量转换：并行转换的线程数（默认为 CPU 核数）
+    #[arg(short = 'j', long)]
+    jobs: Option<usize>,
+
+    /// 监视输入文件（批量转换时监视输入目录），变化后重新转换
+    #[arg(short = 'w', long)]
+    watch: bool,
+
+    /// 合并多个输入时数组的合并方式：replace 整体替换（默认），append 追加，index 按下标合并，key 按 --merge-key 字段配对合并
+    #[arg(long)]
+    array_merge: Option<ArrayMergeStrategy>,
+
+    /// --array-merge key 时用于配对数组元素的字段名（默认 name）
+    #[arg(long)]
+    merge_key: Option<String>,
+
+    /// 合并多个输入时报告每个最终值来自哪个输入文件
+    #[arg(long)]
+    provenance: bool,
+
+    /// 只在内存中转换并与已有的输出文件比较，不写入任何文件；有差异时输出统一 diff 并以非零状态退出
+    #[arg(long, conflicts_with = "watch")]
+    check: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// 比较两个配置文件（可以是不同格式）的内容，列出新增、删除和修改的键路径；有差异时退出码为 1，出错时为 2
+    Diff(DiffArgs),
+
+    /// 按路径表达式查询配置中的值，如 server.tls.port、servers[*].host、servers[?port > 80].host
+    Get(GetArgs),
+
+    /// 修改配置文件中指定路径的值并按原格式写回，如 fmto set config.toml server.port=8080；TOML 文件保留注释和排版
+    Set(SetArgs),
+
+    /// 删除配置文件中指定路径的值并按原格式写回，如 fmto unset config.toml server.tls
+    Unset(UnsetArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct DiffArgs {
+    /// 旧文件路径，- 表示标准输入
+    old: PathBuf,
+
+    /// 新文件路径，- 表示标准输入
+    new: PathBuf,
+
+    /// 旧文件格式（默认根据扩展名或内容判断）
+    #[arg(long)]
+    old_format: Option<String>,
+
+    /// 新文件格式（默认根据扩展名或内容判断）
+    #[arg(long)]
+    new_format: Option<String>,
+
+    /// 差异的输出方式：text 逐行列出，json 输出差异数组，quiet 只通过退出码报告
+    #[arg(long, value_enum, default_value_t = DiffReport::Text)]
+    report: DiffReport,
+
+    /// 按文本比较标量，"8080" 与 8080 视为相同（比较 INI、ENV 等只有字符串的格式时使用）
+    #[arg(long)]
+    ignore_types: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct GetArgs {
+    /// 查询表达式
+    expr: String,
+
+    /// 输入文件路径，- 表示标准输入（默认）
+    #[arg(default_value = "-")]
+    input: PathBuf,
+
+    /// 输入文件格式（默认根据扩展名或内容判断）
+    #[arg(short = 'f', long)]
+    input_format: Option<String>,
+
+    /// 输出格式；默认字符串原样输出、其他值输出为 JSON，多个结果各占一行。指定格式时多个结果合并为一个数组，
+    /// 只能表示对象的格式（如 TOML、INI）要求结果是对象
+    #[arg(short = 't', long)]
+    output_format: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct SetArgs {
+    /// 要修改的文件，- 表示从标准输入读取并写到标准输出
+    input: PathBuf,
+
+    /// 路径=值，可以指定多个；值按 JSON 字面量解析（如 8080、true、[1, 2]、{"a": 1}），不是合法的 JSON 时视为字符串
+    #[arg(required = true)]
+    assignments: Vec<String>,
+
+    /// 输入文件格式（默认根据扩展名或内容判断）
+    #[arg(short = 'f', long)]
+    input_format: Option<String>,
+
+    /// 写到另一个文件而不是修改原文件，- 表示标准输出
+    #[arg(short = 'o', long)]
+    output: Option<PathBuf>,
+
+    /// 值一律作为字符串，不按 JSON 字面量解析
+    #[arg(short = 's', long)]
+    string: bool,
 }
 
-fn ensure_dir_exists(path: &PathBuf) -> Result<()> {
+#[derive(clap::Args, Debug)]
+struct UnsetArgs {
+    /// 要修改的文件，- 表示从标准输入读取并写到标准输出
+    input: PathBuf,
+
+    /// 要删除的路径，可以指定多个
+    #[arg(required = true)]
+    paths: Vec<String>,
+
+    /// 输入文件格式（默认根据扩展名或内容判断）
+    #[arg(short = 'f', long)]
+    input_format: Option<String>,
+
+    /// 写到另一个文件而不是修改原文件，- 表示标准输出
+    #[arg(short = 'o', long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum DiffReport {
+    Text,
+    Json,
+    Quiet,
+}
+
+// 监视模式下，最后一个文件事件之后等待这么久没有新事件才重新转换
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+fn ensure_dir_exists(path: &Path) -> Result<()> {
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)?;
     }
     Ok(())
 }
 
+// 写入输出文件；--check 时不写入，只与已有文件比较，有差异时返回描述差异的统一 diff
+fn emit_output(output_path: &Path, output: &[u8], check: bool) -> Result<Option<String>> {
+    if !check {
+        ensure_dir_exists(output_path)?;
+        std::fs::write(output_path, output)?;
+        return Ok(None);
+    }
+
+    let existing = match std::fs::read(output_path) {
+        Ok(existing) => existing,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(Some(format!("输出文件不存在: {}\n", output_path.display())));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    if existing == output {
+        return Ok(None);
+    }
+    match (std::str::from_utf8(&existing), std::str::from_utf8(output)) {
+        (Ok(old), Ok(new)) => {
+            let name = output_path.display().to_string();
+            let diff = similar::TextDiff::from_lines(old, new);
+            Ok(Some(diff.unified_diff().header(&name, &format!("{}（转换结果）", name)).to_string()))
+        }
+        _ => Ok(Some(format!("二进制文件内容不同: {}\n", output_path.display()))),
+    }
+}
+
+// --check 时报告输出文件是否与转换结果一致，过期时打印 diff 并返回 true
+fn report_check(output_path: &Path, output_format: ConfigFormat, diff: Option<&str>) -> bool {
+    match diff {
+        None => {
+            println!("已是最新: {} ({})", output_path.display(), output_format.to_extension());
+            false
+        }
+        Some(diff) => {
+            println!("需要更新: {} ({})", output_path.display(), output_format.to_extension());
+            print!("{}", diff);
+            true
+        }
+    }
+}
+
+fn check_result(stale: usize) -> Result<()> {
+    if stale == 0 {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("{} 个输出文件与转换结果不一致", stale))
+    }
+}
+
+// - 表示标准输入或标准输出
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+// 输入是目录或包含 glob 通配符时进入批量转换；已存在的文件即使名字含有 [ 或 * 也按单个文件处理
+fn is_batch_input(path: &Path) -> bool {
+    !is_stdio(path) && !path.is_file() && (path.is_dir() || path.to_string_lossy().contains(['*', '?', '[']))
+}
+
+// glob 模式中第一个含通配符的部分之前的目录，作为计算相对路径的基准
+fn glob_base(pattern: &Path) -> PathBuf {
+    pattern
+        .components()
+        .take_while(|c| !c.as_os_str().to_string_lossy().contains(['*', '?', '[']))
+        .collect()
+}
+
+// 展开目录或 glob 模式，按 include/exclude 过滤；返回基准目录和排好序的文件列表
+fn collect_batch_inputs(args: &Args, output_dir: &Path) -> Result<(PathBuf, Vec<PathBuf>)> {
+    let include = args.include.iter().map(|p| glob::Pattern::new(p)).collect::<Result<Vec<_>, _>>()?;
+    let exclude = args.exclude.iter().map(|p| glob::Pattern::new(p)).collect::<Result<Vec<_>, _>>()?;
+
+    let input = &args.input[0];
+    let (base, candidates) = if input.is_dir() {
+        // 输出目录位于输入目录内时跳过，避免把上一次的输出再转换一遍
+        let output_root = output_dir.canonicalize().ok();
+        let files: Vec<PathBuf> = walkdir::WalkDir::new(input)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| !entry.file_type().is_dir() || entry.path().canonicalize().ok() != output_root)
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .collect();
+        // 没有 include 时只转换能从文件名识别格式的文件
+        let files = files
+            .into_iter()
+            .filter(|path| !include.is_empty() || ConfigFormat::from_path(path).is_some())
+            .collect();
+        (input.clone(), files)
+    } else {
+        let pattern = input.to_str().ok_or_else(|| anyhow::anyhow!("无效的输入路径: {}", input.display()))?;
+        let mut files: Vec<PathBuf> = glob::glob(pattern)?.filter_map(|path| path.ok()).filter(|path| path.is_file()).collect();
+        files.sort();
+        (glob_base(input), files)
+    };
+
+    let files: Vec<PathBuf> = candidates
+        .into_iter()
+        .filter(|path| {
+            let relative = path.strip_prefix(&base).unwrap_or(path);
+            (include.is_empty() || include.iter().any(|p| p.matches_path(relative)))
+                && !exclude.iter().any(|p| p.matches_path(relative))
+        })
+        .collect();
+
+    // 输出文件名只保留输入的文件名主干，同一目录下的 a.json 和 a.yaml 会写到同一个输出文件
+    let mut stems: BTreeMap<PathBuf, &PathBuf> = BTreeMap::new();
+    for path in &files {
+        let relative = path.strip_prefix(&base).unwrap_or(path);
+        if let Some(previous) = stems.insert(relative.with_extension(""), path) {
+            return Err(anyhow::anyhow!(
+                "{} 和 {} 会转换到同一个输出文件，请用 --exclude 排除其中一个",
+                previous.display(),
+                path.display()
+            ));
+        }
+    }
+    Ok((base, files))
+}
+
+// 批量转换的输出目标：扩展名、格式和各工作线程共享的转换器
+type OutputTarget = (String, ConfigFormat, Box<dyn ConfigConverter>);
+
+// 转换单个文件，输出路径为输出目录下与输入相同的相对目录，文件名换成各输出格式的扩展名
+fn convert_batch_file(
+    file: &Path,
+    relative: &Path,
+    input_format: Option<ConfigFormat>,
+    outputs: &[OutputTarget],
+    output_dir: &Path,
+    format_options: &FormatOptions,
+    check: bool,
+) -> Result<Vec<(PathBuf, ConfigFormat, Option<String>)>> {
+    let input = std::fs::read(file)?;
+    let format = match input_format.or_else(|| ConfigFormat::from_path(file)) {
+        Some(format) => format,
+        None => ConfigFormat::detect(&String::from_utf8_lossy(&input))?,
+    };
+    let (config, comments) = parse_keeping_comments(format, &input, format_options)?;
+
+    let file_stem = relative
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| anyhow::anyhow!("无法获取输入文件名"))?;
+    let target_dir = output_dir.join(relative.parent().unwrap_or(Path::new("")));
+
+    let mut written = Vec::new();
+    for (ext, output_format, converter) in outputs {
+        let output_path = target_dir.join(format!("{}.{}", file_stem, ext));
+        let output = format_keeping_comments(*output_format, converter.as_ref(), &config, &comments)?;
+        let diff = emit_output(&output_path, &output, check)?;
+        written.push((output_path, *output_format, diff));
+    }
+    Ok(written)
+}
+
+// 批量转换：多个工作线程从共享的文件列表中取任务；单个文件失败不会中止，日志按文件顺序输出，最后汇总成功和失败的文件
+fn convert_batch(args: &Args, format_options: &FormatOptions) -> Result<()> {
+    let output_dir = args.output_dir.as_ref().ok_or_else(|| anyhow::anyhow!("批量转换时必须用 -d 指定输出目录"))?;
+    if !args.output.is_empty() {
+        return Err(anyhow::anyhow!("批量转换不支持 -o，请用 -d 指定输出目录"));
+    }
+    if args.output_format.is_empty() {
+        return Err(anyhow::anyhow!("批量转换时必须用 -t 指定输出格式"));
+    }
+    let outputs = args.output_format
+        .iter()
+        .map(|ext| {
+            ConfigFormat::from_extension(ext)
+                .map(|format| (ext.clone(), format, ConfigConverterFactory::get_converter_with_options(format, format_options)))
+                .ok_or_else(|| anyhow::anyhow!("无法确定输出文件格式: {}", ext))
+        })
+        .collect::<Result<Vec<OutputTarget>>>()?;
+    let input_format = args.input_format.as_deref().and_then(ConfigFormat::from_extension);
+
+    if !args.check {
+        std::fs::create_dir_all(output_dir)?;
+    }
+    let (base, files) = collect_batch_inputs(args, output_dir)?;
+
+    let jobs = args.jobs
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+        .clamp(1, files.len().max(1));
+
+    let mut succeeded = 0;
+    let mut stale = 0;
+    let mut failures = Vec::new();
+    let next_file = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            let sender = sender.clone();
+            let (files, base, next_file, outputs) = (&files, &base, &next_file, &outputs);
+            scope.spawn(move || loop {
+                let index = next_file.fetch_add(1, Ordering::Relaxed);
+                let Some(file) = files.get(index) else {
+                    break;
+                };
+                let relative = file.strip_prefix(base).unwrap_or(file);
+                let result = convert_batch_file(file, relative, input_format, outputs, output_dir, format_options, args.check);
+                if sender.send((index, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        // 先完成的结果暂存起来，等前面的文件都报告后再输出，保证日志顺序与文件顺序一致
+        let mut pending = BTreeMap::new();
+        let mut next_report = 0;
+        for (index, result) in receiver {
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&next_report) {
+                let file = &files[next_report];
+                match result {
+                    Ok(written) => {
+                        succeeded += 1;
+                        for (output_path, output_format, diff) in written {
+                            if args.check {
+                                stale += report_check(&output_path, output_format, diff.as_deref()) as usize;
+                            } else {
+                                println!("已转换: {} -> {} ({})", file.display(), output_path.display(), output_format.to_extension());
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("转换失败: {}: {}", file.display(), e);
+                        failures.push((file, e));
+                    }
+                }
+                next_report += 1;
+            }
+        }
+    });
+
+    println!("\n批量转换完成！成功 {} 个文件，失败 {} 个文件", succeeded, failures.len());
+    if !failures.is_empty() {
+        eprintln!("\n失败的文件:");
+        for (file, e) in &failures {
+            eprintln!("  {}: {}", file.display(), e);
+        }
+        return Err(anyhow::anyhow!("{} 个文件转换失败", failures.len()));
+    }
+    check_result(stale)
+}
+
 fn main() -> Result<()> {
     let args = Args::parse();
 
-    // 从文件扩展名确定输入格式
-    let input_format = args.input_format
-        .map(|f| ConfigFormat::from_extension(&f))
-        .flatten()
-        .or_else(|| {
-            args.input.extension()
-                .and_then(|ext| ext.to_str())
-                .and_then(|ext| ConfigFormat::from_extension(ext))
-        })
-        .ok_or_else(|| anyhow::anyhow!("无法确定输入文件格式"))?;
+    let format_options = FormatOptions {
+        json: JsonOptions {
+            compact: args.json_compact,
+            indent: args.json_indent,
+            ascii: args.json_ascii,
+            trailing_newline: args.json_trailing_newline,
+            canonical: false,
+        },
+        toml: TomlOptions {
+            inline_tables: args.toml_inline_tables,
+            array_of_tables: !args.toml_inline_arrays,
+            max_inline_width: if args.toml_max_width == 0 { None } else { Some(args.toml_max_width) },
+        },
+        properties: PropertiesOptions {
+            unicode_escape: !args.properties_utf8,
+        },
+        plist: PlistOptions {
+            binary: args.plist_binary,
+        },
+        hcl: HclOptions {
+            blocks: args.hcl_blocks,
+        },
+        csv: CsvOptions {
+            infer_types: args.csv_infer_types,
+            ..CsvOptions::default()
+        },
+    };
+
+    match &args.command {
+        Some(Command::Diff(diff_args)) => {
+            // 与 diff(1) 一致：没有差异返回 0，有差异返回 1，出错返回 2
+            let code = match diff(diff_args, &format_options) {
+                Ok(false) => 0,
+                Ok(true) => 1,
+                Err(e) => {
+                    eprintln!("Error: {:?}", e);
+                    2
+                }
+            };
+            std::process::exit(code);
+        }
+        Some(Command::Get(get_args)) => get(get_args, &format_options),
+        Some(Command::Set(set_args)) => {
+            let edits = set_args
+                .assignments
+                .iter()
+                .map(|assignment| parse_assignment(assignment, set_args.string))
+                .collect::<Result<Vec<_>>>()?;
+            edit(&set_args.input, set_args.input_format.as_deref(), set_args.output.as_deref(), &edits, &format_options)
+        }
+        Some(Command::Unset(unset_args)) => {
+            let edits = unset_args
+                .paths
+                .iter()
+                .map(|path| Ok((parse_edit_path(path)?, None)))
+                .collect::<Result<Vec<_>>>()?;
+            edit(&unset_args.input, unset_args.input_format.as_deref(), unset_args.output.as_deref(), &edits, &format_options)
+        }
+        None if args.watch => watch(&args, &format_options),
+        None => convert(&args, &format_options),
+    }
+}
+
+// 按字节读取输入（- 表示标准输入）：plist、MessagePack、CBOR 是二进制，properties 文件可能是 ISO-8859-1 编码
+fn read_input(path: &Path) -> Result<Vec<u8>> {
+    if is_stdio(path) {
+        let mut buf = Vec::new();
+        std::io::stdin().read_to_end(&mut buf)?;
+        Ok(buf)
+    } else {
+        std::fs::read(path).map_err(|e| anyhow::anyhow!("无法读取 {}: {}", path.display(), e))
+    }
+}
 
-    // 读取输入文件
-    let content = std::fs::read_to_string(&args.input)?;
+// 格式依次取自指定的格式、文件扩展名（或 .gitconfig 这类文件名）和内容嗅探
+fn resolve_format(path: &Path, format: Option<&str>, input: &[u8]) -> Result<ConfigFormat> {
+    match format.and_then(ConfigFormat::from_extension).or_else(|| ConfigFormat::from_path(path)) {
+        Some(format) => Ok(format),
+        None => ConfigFormat::detect(&String::from_utf8_lossy(input))
+            .map_err(|e| anyhow::anyhow!("无法确定 {} 的格式，请指定格式: {}", path.display(), e)),
+    }
+}
 
-    // 获取输入转换器并解析
-    let input_converter = ConfigConverterFactory::get_converter(input_format);
-    let config = input_converter.parse(&content)?;
+// 读取输入并解析
+fn load_input(path: &Path, format: Option<&str>, format_options: &FormatOptions) -> Result<(ConfigFormat, ConfigValue)> {
+    let input = read_input(path)?;
+    let input_format = resolve_format(path, format, &input)?;
+    let config = ConfigConverterFactory::get_converter_with_options(input_format, format_options).parse_bytes(&input)?;
+    Ok((input_format, config))
+}
+
+// JSON5 输入的注释按所属路径保留下来，输出为 JSON5 时写回；其他格式没有注释
+fn parse_keeping_comments(format: ConfigFormat, input: &[u8], format_options: &FormatOptions) -> Result<(ConfigValue, HashMap<String, String>)> {
+    if format == ConfigFormat::Json5 {
+        return Json5Converter.parse_with_comments(std::str::from_utf8(input)?);
+    }
+    let config = ConfigConverterFactory::get_converter_with_options(format, format_options).parse_bytes(input)?;
+    Ok((config, HashMap::new()))
+}
+
+fn format_keeping_comments(
+    format: ConfigFormat,
+    converter: &dyn ConfigConverter,
+    config: &ConfigValue,
+    comments: &HashMap<String, String>,
+) -> Result<Vec<u8>> {
+    if format == ConfigFormat::Json5 {
+        return Ok(Json5Converter.format_with_comments(config, comments)?.into_bytes());
+    }
+    converter.format_bytes(config)
+}
+
+// 修改的目标路径只能由键和非负下标组成
+fn parse_edit_path(expr: &str) -> Result<Vec<PathSegment>> {
+    parse_query(expr)?
+        .to_path()
+        .filter(|path| !path.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("无效的路径 {}: 不能包含通配符、过滤条件或负数下标", expr))
+}
+
+// 拆分 路径=值，引号内的 = 属于键名
+fn parse_assignment(assignment: &str, string: bool) -> Result<(Vec<PathSegment>, Option<serde_json::Value>)> {
+    let mut quoted = false;
+    let mut escaped = false;
+    let position = assignment.char_indices().find_map(|(i, c)| {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '=' if !quoted => return Some(i),
+            _ => {}
+        }
+        None
+    });
+    let position = position.ok_or_else(|| anyhow::anyhow!("无效的赋值 {}，应为 路径=值", assignment))?;
+    let (path, raw) = (&assignment[..position], &assignment[position + 1..]);
+    let value = if string {
+        serde_json::Value::String(raw.to_string())
+    } else {
+        serde_json::from_str(raw).unwrap_or_else(|_| serde_json::Value::String(raw.to_string()))
+    };
+    Ok((parse_edit_path(path)?, Some(value)))
+}
+
+// 依次执行修改（值为 None 表示删除）并按原格式写回；有保留格式的编辑器时直接修改原文，否则解析后重新格式化
+fn edit(
+    input: &Path,
+    format: Option<&str>,
+    output: Option<&Path>,
+    edits: &[(Vec<PathSegment>, Option<serde_json::Value>)],
+    format_options: &FormatOptions,
+) -> Result<()> {
+    let content = read_input(input)?;
+    let input_format = resolve_format(input, format, &content)?;
+
+    let result = match (ConfigConverterFactory::get_editor(input_format), String::from_utf8(content)) {
+        (Some(editor), Ok(mut text)) => {
+            for (path, value) in edits {
+                text = match value {
+                    Some(value) => editor.set(&text, path, value)?,
+                    None => editor.remove(&text, path)?,
+                };
+            }
+            text.into_bytes()
+        }
+        (_, content) => {
+            let content = content.map(String::into_bytes).unwrap_or_else(|e| e.into_bytes());
+            let converter = ConfigConverterFactory::get_converter_with_options(input_format, format_options);
+            let mut config = converter.parse_bytes(&content)?;
+            for (path, value) in edits {
+                match value {
+                    Some(value) => set_path(&mut config, path, value.clone())?,
+                    None => {
+                        remove_path(&mut config, path)?;
+                    }
+                }
+            }
+            converter.format_bytes(&config)?
+        }
+    };
+
+    // 从标准输入读取时写到标准输出，否则默认写回原文件
+    let output = output.unwrap_or(input);
+    if is_stdio(output) {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(&result)?;
+        stdout.flush()?;
+    } else {
+        emit_output(output, &result, false)?;
+        println!("已更新: {} ({})", output.display(), input_format.to_extension());
+    }
+    Ok(())
+}
+
+// 比较两个配置文件并按指定方式报告，返回是否有差异
+fn diff(args: &DiffArgs, format_options: &FormatOptions) -> Result<bool> {
+    if is_stdio(&args.old) && is_stdio(&args.new) {
+        return Err(anyhow::anyhow!("只能有一个输入来自标准输入"));
+    }
+    let (_, old) = load_input(&args.old, args.old_format.as_deref(), format_options)?;
+    let (_, new) = load_input(&args.new, args.new_format.as_deref(), format_options)?;
+    let changes = diff_configs(&old, &new, &DiffOptions { ignore_types: args.ignore_types });
+
+    match args.report {
+        DiffReport::Text => {
+            for change in &changes {
+                match change {
+                    ConfigChange::Added { path, value } => println!("+ {}: {}", path, value),
+                    ConfigChange::Removed { path, value } => println!("- {}: {}", path, value),
+                    ConfigChange::Changed { path, old, new } => println!("~ {}: {} -> {}", path, old, new),
+                }
+            }
+            if changes.is_empty() {
+                println!("没有差异");
+            } else {
+                let count = |f: fn(&ConfigChange) -> bool| changes.iter().filter(|c| f(c)).count();
+                println!(
+                    "\n共 {} 处差异：新增 {}，删除 {}，修改 {}",
+                    changes.len(),
+                    count(|c| matches!(c, ConfigChange::Added { .. })),
+                    count(|c| matches!(c, ConfigChange::Removed { .. })),
+                    count(|c| matches!(c, ConfigChange::Changed { .. })),
+                );
+            }
+        }
+        DiffReport::Json => println!("{}", serde_json::to_string_pretty(&changes)?),
+        DiffReport::Quiet => {}
+    }
+
+    Ok(!changes.is_empty())
+}
+
+// 查询并输出匹配的值；没有匹配时返回错误
+fn get(args: &GetArgs, format_options: &FormatOptions) -> Result<()> {
+    let query = parse_query(&args.expr)?;
+    let (_, config) = load_input(&args.input, args.input_format.as_deref(), format_options)?;
+    let mut results = query.select(&config);
+    if results.is_empty() {
+        return Err(anyhow::anyhow!("没有匹配的值: {}", args.expr));
+    }
+
+    let Some(format) = &args.output_format else {
+        for result in &results {
+            match result {
+                serde_json::Value::String(s) => println!("{}", s),
+                other => println!("{}", serde_json::to_string_pretty(other)?),
+            }
+        }
+        return Ok(());
+    };
+
+    let output_format = ConfigFormat::from_extension(format).ok_or_else(|| anyhow::anyhow!("不支持的输出格式: {}", format))?;
+    let result = if query.is_single() { results.remove(0) } else { serde_json::Value::Array(results) };
+    let output = match result {
+        serde_json::Value::Object(map) => {
+            let config = ConfigValue { value: map.into_iter().collect() };
+            ConfigConverterFactory::get_converter_with_options(output_format, format_options).format_bytes(&config)?
+        }
+        other => match output_format {
+            ConfigFormat::Json => serde_json::to_string_pretty(&other)?.into_bytes(),
+            ConfigFormat::Yaml => serde_yaml::to_string(&other)?.into_bytes(),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "查询结果不是对象，无法输出为 {}，请改用 json 或 yaml",
+                    output_format.to_extension()
+                ))
+            }
+        },
+    };
+
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(&output)?;
+    // 文本输出补上结尾的换行，二进制格式原样输出
+    if !output.ends_with(b"\n") && std::str::from_utf8(&output).is_ok() {
+        stdout.write_all(b"\n")?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+fn convert(args: &Args, format_options: &FormatOptions) -> Result<()> {
+    if args.input.len() > 1 {
+        if args.input.iter().any(|input| is_batch_input(input)) {
+            return Err(anyhow::anyhow!("合并多个输入时不支持目录或 glob 模式"));
+        }
+        if args.input_format.is_some() {
+            return Err(anyhow::anyhow!("合并多个输入时不支持 -f，各输入的格式根据扩展名或内容判断"));
+        }
+        if args.input.iter().filter(|input| is_stdio(input)).count() > 1 {
+            return Err(anyhow::anyhow!("只能有一个输入来自标准输入"));
+        }
+    } else if args.array_merge.is_some() || args.merge_key.is_some() || args.provenance {
+        return Err(anyhow::anyhow!("--array-merge、--merge-key 和 --provenance 只能在合并多个输入时使用"));
+    }
+    if is_batch_input(&args.input[0]) {
+        convert_batch(args, format_options)
+    } else {
+        convert_single(args, format_options)
+    }
+}
+
+// 监视模式：先转换一次，之后输入变化时重新转换；转换出错只报告，不退出
+fn watch(args: &Args, format_options: &FormatOptions) -> Result<()> {
+    if args.input.iter().any(|input| is_stdio(input)) {
+        return Err(anyhow::anyhow!("--watch 需要输入文件，不支持标准输入"));
+    }
+
+    // 批量转换时递归监视输入目录；否则监视各输入文件所在的目录而不是文件本身，编辑器保存时常用重命名替换文件，直接监视文件会丢失后续事件
+    let batch = is_batch_input(&args.input[0]);
+    let mut watch_roots: Vec<(PathBuf, RecursiveMode)> = Vec::new();
+    let mut input_paths = Vec::new();
+    if batch {
+        let input = &args.input[0];
+        let root = if input.is_dir() { input.clone() } else { glob_base(input) };
+        // '*.yaml' 这类没有目录前缀的模式基准目录为空，监视当前目录
+        let root = if root.as_os_str().is_empty() { PathBuf::from(".") } else { root };
+        watch_roots.push((root.canonicalize()?, RecursiveMode::Recursive));
+    } else {
+        for input in &args.input {
+            let parent = input.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new(".")).canonicalize()?;
+            input_paths.extend(input.canonicalize().ok());
+            input_paths.extend(input.file_name().map(|name| parent.join(name)));
+            if !watch_roots.iter().any(|(root, _)| *root == parent) {
+                watch_roots.push((parent, RecursiveMode::NonRecursive));
+            }
+        }
+    }
+
+    if let Err(e) = convert(args, format_options) {
+        eprintln!("转换失败: {}", e);
+    }
+    // 批量转换时输出目录在第一次转换后才存在，此时再解析其绝对路径，用于忽略自身写出的文件
+    let output_root = args.output_dir.as_ref().and_then(|dir| dir.canonicalize().ok());
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    for (root, mode) in &watch_roots {
+        watcher.watch(root, *mode)?;
+    }
+    let names: Vec<_> = args.input.iter().map(|input| input.display().to_string()).collect();
+    println!("\n正在监视 {} 的变化，按 Ctrl+C 退出", names.join(", "));
+
+    let is_relevant = |event: &notify::Event| {
+        !matches!(event.kind, EventKind::Access(_))
+            && event.paths.iter().any(|path| {
+                if batch {
+                    !output_root.as_ref().is_some_and(|root| path.starts_with(root))
+                } else {
+                    input_paths.contains(path)
+                }
+            })
+    };
+
+    loop {
+        match receiver.recv()? {
+            Ok(event) if is_relevant(&event) => {}
+            Ok(_) => continue,
+            Err(e) => {
+                eprintln!("监视出错: {}", e);
+                continue;
+            }
+        }
+        // 去抖：一次保存往往触发多个事件，等到一段时间内没有新事件后再转换
+        while receiver.recv_timeout(WATCH_DEBOUNCE).is_ok() {}
+
+        println!("\n检测到变化，重新转换...");
+        if let Err(e) = convert(args, format_options) {
+            eprintln!("转换失败: {}", e);
+        }
+    }
+}
+
+fn convert_single(args: &Args, format_options: &FormatOptions) -> Result<()> {
+    let input = &args.input[0];
+    let mut provenance = Vec::new();
+    let mut comments = HashMap::new();
+    let (input_format, config) = if args.input.len() > 1 {
+        // 合并多个输入：输出格式默认沿用第一个输入的格式
+        let mut layers = Vec::new();
+        let mut input_format = None;
+        for path in &args.input {
+            let (format, config) = load_input(path, None, format_options)?;
+            input_format.get_or_insert(format);
+            layers.push(config);
+        }
+        let defaults = MergeOptions::default();
+        let options = MergeOptions {
+            arrays: args.array_merge.unwrap_or(defaults.arrays),
+            merge_key: args.merge_key.clone().unwrap_or(defaults.merge_key),
+        };
+        let merged = merge_configs(&layers, &options);
+        provenance = merged.provenance;
+        (input_format.unwrap_or(ConfigFormat::Json), merged.config)
+    } else {
+        let content = read_input(input)?;
+        let input_format = resolve_format(input, args.input_format.as_deref(), &content)?;
+        let (config, input_comments) = parse_keeping_comments(input_format, &content, format_options)?;
+        comments = input_comments;
+        (input_format, config)
+    };
+    let input_name = args.input
+        .iter()
+        .map(|path| if is_stdio(path) { "<stdin>".to_string() } else { path.display().to_string() })
+        .collect::<Vec<_>>()
+        .join(" + ");
 
     // 确定输出文件列表
     let output_files = if !args.output.is_empty() {
-        args.output
-    } else if let Some(output_dir) = args.output_dir {
+        args.output.clone()
+    } else if (is_stdio(input) || args.input.len() > 1) && args.output_dir.is_none() {
+        // 从标准输入读取或合并多个输入且没有指定输出目标时，写到标准输出，避免覆盖输入文件
+        vec![PathBuf::from("-"); args.output_format.len().max(1)]
+    } else if let Some(output_dir) = &args.output_dir {
         // 如果指定了输出目录但没有指定输出文件，则使用输入文件名加上所有输出格式的扩展名
-        let file_stem = args.input.file_stem()
+        let file_stem = input.file_stem()
+            .filter(|_| !is_stdio(input))
             .and_then(|s| s.to_str())
             .ok_or_else(|| anyhow::anyhow!("无法获取输入文件名"))?;
         
-        // 确保输出目录存在
-        std::fs::create_dir_all(&output_dir)?;
-        
-        // 如果没有指定输出格式，则使用所有支持的格式
+        // 如果没有指定输出格式，则使用所有通用格式；gitconfig、systemd、CSV 等对数据结构有要求的格式需显式指定
         let formats = if args.output_format.is_empty() {
-            vec!["json", "yaml", "toml", "ini", "xml", "hocon", "env"]
+            vec!["json", "json5", "yaml", "toml", "ini", "xml", "hocon", "env", "properties", "plist", "hcl", "ron", "kdl", "msgpack", "cbor"]
                 .into_iter()
                 .map(String::from)
                 .collect()
@@ -83,7 +920,7 @@ fn main() -> Result<()> {
             .collect()
     } else {
         // 如果没有指定输出文件或输出目录，则使用输入文件名加上输出格式的扩展名
-        let file_stem = args.input.file_stem()
+        let file_stem = input.file_stem()
             .and_then(|s| s.to_str())
             .ok_or_else(|| anyhow::anyhow!("无法获取输入文件名"))?;
         
@@ -99,35 +936,67 @@ fn main() -> Result<()> {
             .collect()
     };
 
+    // 转换结果写到标准输出时，进度信息改写到标准错误，避免混入管道
+    let to_stdout = output_files.iter().any(|path| is_stdio(path));
+    if to_stdout && args.check {
+        return Err(anyhow::anyhow!("--check 需要与已有的输出文件比较，不能输出到标准输出"));
+    }
+    let report = |message: String| {
+        if to_stdout {
+            eprintln!("{}", message);
+        } else {
+            println!("{}", message);
+        }
+    };
+
+    if args.provenance && !provenance.is_empty() {
+        report("值的来源:".to_string());
+        for (path, layer) in &provenance {
+            report(format!("  {} <- {}", path, args.input[*layer].display()));
+        }
+    }
+
     // 转换并写入所有输出文件
+    let mut stale = 0;
     for (i, output_path) in output_files.iter().enumerate() {
-        // 确定输出格式
+        // 确定输出格式，写到标准输出且没有指定格式时沿用输入格式
         let output_format = if i < args.output_format.len() {
             ConfigFormat::from_extension(&args.output_format[i])
+        } else if is_stdio(output_path) {
+            Some(input_format)
         } else {
-            output_path.extension()
-                .and_then(|ext| ext.to_str())
-                .and_then(|ext| ConfigFormat::from_extension(ext))
+            ConfigFormat::from_path(output_path)
         }.ok_or_else(|| anyhow::anyhow!("无法确定输出文件格式: {}", output_path.display()))?;
 
-        // 获取输出转换器并格式化
-        let output_converter = ConfigConverterFactory::get_converter(output_format);
-        let output = output_converter.format(&config)?;
-
-        // 确保输出目录存在
-        ensure_dir_exists(output_path)?;
+        // 获取输出转换器并按字节格式化，二进制格式原样写入
+        let output_converter = ConfigConverterFactory::get_converter_with_options(output_format, format_options);
+        let output = format_keeping_comments(output_format, output_converter.as_ref(), &config, &comments)?;
 
-        // 写入输出文件
-        std::fs::write(output_path, output)?;
+        if is_stdio(output_path) {
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(&output)?;
+            stdout.flush()?;
+        } else if args.check {
+            // 只比较，不写入
+            let diff = emit_output(output_path, &output, true)?;
+            stale += report_check(output_path, output_format, diff.as_deref()) as usize;
+            continue;
+        } else {
+            // 写入输出文件，必要时创建输出目录
+            emit_output(output_path, &output, false)?;
+        }
 
-        println!("已转换: {} -> {} ({})", 
-            args.input.display(), 
+        report(format!("已转换: {} -> {} ({})", 
+            input_name, 
             output_path.display(), 
             output_format.to_extension()
-        );
+        ));
     }
 
-    println!("\n转换完成！共转换 {} 个文件", output_files.len());
+    if args.check {
+        return check_result(stale);
+    }
+    report(format!("\n转换完成！共转换 {} 个文件", output_files.len()));
 
     Ok(())
 }
@@ -0,0 +1,286 @@
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+// 按 java.util.Properties#load 的语法切分出键值对
+pub fn parse_entries(input: &str) -> Result<Vec<(String, String)>> {
+    let mut entries = Vec::new();
+    let mut lines = input.lines();
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start_matches([' ', '\t', '\u{c}']);
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('!') {
+            continue;
+        }
+
+        // 以奇数个反斜杠结尾的行与下一行拼接，下一行的前导空白被忽略
+        let mut logical = trimmed.to_string();
+        while ends_with_continuation(&logical) {
+            logical.pop();
+            match lines.next() {
+                Some(next) => logical.push_str(next.trim_start_matches([' ', '\t', '\u{c}'])),
+                None => break,
+            }
+        }
+
+        let chars: Vec<char> = logical.chars().collect();
+        let mut position = 0;
+        let mut key_end = chars.len();
+        while position < chars.len() {
+            match chars[position] {
+                '\\' => position += 2,
+                '=' | ':' | ' ' | '\t' | '\u{c}' => {
+                    key_end = position;
+                    break;
+                }
+                _ => position += 1,
+            }
+        }
+        let key_end = key_end.min(chars.len());
+
+        let mut value_start = key_end;
+        while value_start < chars.len() && matches!(chars[value_start], ' ' | '\t' | '\u{c}') {
+            value_start += 1;
+        }
+        if value_start < chars.len() && matches!(chars[value_start], '=' | ':') {
+            value_start += 1;
+            while value_start < chars.len() && matches!(chars[value_start], ' ' | '\t' | '\u{c}') {
+                value_start += 1;
+            }
+        }
+
+        let key = unescape(&chars[..key_end])?;
+        let value = unescape(&chars[value_start.min(chars.len())..])?;
+        entries.push((key, value));
+    }
+
+    Ok(entries)
+}
+
+fn ends_with_continuation(line: &str) -> bool {
+    line.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1
+}
+
+fn unescape(chars: &[char]) -> Result<String> {
+    let mut result = String::new();
+    let mut position = 0;
+    while position < chars.len() {
+        let c = chars[position];
+        position += 1;
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        let Some(&escaped) = chars.get(position) else {
+            break;
+        };
+        position += 1;
+        match escaped {
+            't' => result.push('\t'),
+            'n' => result.push('\n'),
+            'r' => result.push('\r'),
+            'f' => result.push('\u{c}'),
+            'u' => {
+                let hex: String = chars.iter().skip(position).take(4).collect();
+                let mut code = u32::from_str_radix(&hex, 16).map_err(|_| anyhow!("无效的 Unicode 转义: \\u{}", hex))?;
+                position += 4;
+                // UTF-16 代理对由两个连续的 \uXXXX 组成
+                if (0xD800..0xDC00).contains(&code) && chars.get(position) == Some(&'\\') && chars.get(position + 1) == Some(&'u') {
+                    let low: String = chars.iter().skip(position + 2).take(4).collect();
+                    if let Ok(low) = u32::from_str_radix(&low, 16) {
+                        code = 0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+                        position += 6;
+                    }
+                }
+                result.push(char::from_u32(code).ok_or_else(|| anyhow!("无效的 Unicode 转义: \\u{}", hex))?);
+            }
+            other => result.push(other),
+        }
+    }
+    Ok(result)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+// 拆分 Spring 风格的键：server.port、servers[0].host、map[a.b]
+fn split_key(key: &str) -> Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut chars = key.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                if !current.is_empty() {
+                    segments.push(Segment::Key(std::mem::take(&mut current)));
+                }
+            }
+            '[' => {
+                if !current.is_empty() {
+                    segments.push(Segment::Key(std::mem::take(&mut current)));
+                }
+                let inner: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                match inner.parse::<usize>() {
+                    Ok(index) => segments.push(Segment::Index(index)),
+                    Err(_) => segments.push(Segment::Key(inner)),
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        segments.push(Segment::Key(current));
+    }
+
+    if segments.is_empty() {
+        return Err(anyhow!("无效的键: {}", key));
+    }
+    Ok(segments)
+}
+
+// 数组下标的上限，避免 a[100000000000]=x 这样的键分配过多内存
+const MAX_ARRAY_INDEX: usize = 100_000;
+
+// 按路径写入值；同一路径重复出现时后面的值覆盖前面的值。
+// 路径经过一个已有的标量（如先有 a=1 再有 a.b=2）时返回 false，不做任何修改
+fn insert_path(target: &mut Value, segments: &[Segment], value: Value) -> Result<bool> {
+    let Some((first, rest)) = segments.split_first() else {
+        if target.is_object() || target.is_array() {
+            return Ok(false);
+        }
+        *target = value;
+        return Ok(true);
+    };
+
+    let child = match first {
+        Segment::Key(name) => {
+            if target.is_null() {
+                *target = Value::Object(Map::new());
+            }
+            let Some(obj) = target.as_object_mut() else {
+                return Ok(false);
+            };
+            obj.entry(name.clone()).or_insert(Value::Null)
+        }
+        Segment::Index(index) => {
+            if target.is_null() {
+                *target = Value::Array(Vec::new());
+            }
+            let Some(arr) = target.as_array_mut() else {
+                return Ok(false);
+            };
+            let len = index
+                .checked_add(1)
+                .filter(|len| *len <= MAX_ARRAY_INDEX + 1)
+                .ok_or_else(|| anyhow!("数组下标 {} 超出上限 {}", index, MAX_ARRAY_INDEX))?;
+            if arr.len() < len {
+                arr.resize(len, Value::Null);
+            }
+            &mut arr[*index]
+        }
+    };
+    insert_path(child, rest, value)
+}
+
+// 与 java.util.Properties 一致，重复的键以最后一次出现的值为准。
+// Spring 配置中常见同一个键既有值又有子键（server=x 与 server.port=80）：先写入层级较浅的键，
+// 与已有标量冲突的子键不再展开，以原样的键名保存在顶层，如 {"server": "x", "server.port": "80"}，输出时按原样写回
+pub fn parse_properties(input: &str) -> Result<HashMap<String, Value>> {
+    let mut latest: HashMap<String, String> = HashMap::new();
+    let mut order = Vec::new();
+    for (key, value) in parse_entries(input)? {
+        if latest.insert(key.clone(), value).is_none() {
+            order.push(key);
+        }
+    }
+
+    let mut entries = Vec::new();
+    for key in order {
+        let segments = split_key(&key)?;
+        if matches!(segments.first(), Some(Segment::Index(_))) {
+            return Err(anyhow!("顶层键不能是数组下标: {}", key));
+        }
+        let value = latest.remove(&key).unwrap_or_default();
+        entries.push((segments, key, value));
+    }
+    entries.sort_by_key(|(segments, _, _)| segments.len());
+
+    let mut root = Value::Object(Map::new());
+    for (segments, key, value) in entries {
+        let inserted = insert_path(&mut root, &segments, Value::String(value.clone())).map_err(|e| anyhow!("无效的键 {}: {}", key, e))?;
+        if !inserted {
+            if let Value::Object(map) = &mut root {
+                map.insert(key, Value::String(value));
+            }
+        }
+    }
+
+    match root {
+        Value::Object(map) => Ok(map.into_iter().collect()),
+        _ => Err(anyhow!("properties 根节点必须是对象")),
+    }
+}
+
+// 将嵌套结构展开为 Spring 风格的键，含 . 或 [ 的对象键写成 [key] 形式
+pub fn flatten(prefix: &str, value: &Value, output: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(obj) => {
+            for (k, v) in obj {
+                let key = if k.contains(['.', '[', ']']) {
+                    format!("{}[{}]", prefix, k)
+                } else if prefix.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{}.{}", prefix, k)
+                };
+                flatten(&key, v, output);
+            }
+        }
+        Value::Array(arr) => {
+            for (i, v) in arr.iter().enumerate() {
+                flatten(&format!("{}[{}]", prefix, i), v, output);
+            }
+        }
+        Value::String(s) => output.push((prefix.to_string(), s.clone())),
+        Value::Null => output.push((prefix.to_string(), String::new())),
+        other => output.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+pub fn escape(text: &str, is_key: bool, unicode_escape: bool) -> String {
+    let mut result = String::new();
+    for (i, c) in text.chars().enumerate() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '\t' => result.push_str("\\t"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\u{c}' => result.push_str("\\f"),
+            '=' | ':' | '#' | '!' if is_key || i == 0 => {
+                result.push('\\');
+                result.push(c);
+            }
+            ' ' if is_key || i == 0 => result.push_str("\\ "),
+            c if unicode_escape && !c.is_ascii() => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    result.push_str(&format!("\\u{:04X}", unit));
+                }
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+// 旧式 properties 文件使用 ISO-8859-1 编码，不是合法 UTF-8 时按 ISO-8859-1 解码
+pub fn decode(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.to_string(),
+        Err(_) => bytes.iter().map(|&b| b as char).collect(),
+    }
+}
@@ -0,0 +1,463 @@
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+// RON 解析器，映射规则与 serde 在 JSON 中的默认表示保持一致：
+// - 结构体 `(a: 1)` 或 `Name(a: 1)` 映射为对象，结构体名被丢弃
+// - 元组 `(1, 2)` 和列表 `[1, 2]` 映射为数组，单元 `()` 映射为 null
+// - 枚举按外部标签表示：单元变体 `Variant` 映射为字符串 "Variant"，
+//   元组变体 `Variant(x)` 映射为 {"Variant": x}，`Variant(x, y)` 映射为 {"Variant": [x, y]}
+// - Option 的 `Some(x)` 映射为 x，`None` 映射为 null
+// - 字典 `{k: v}` 映射为对象，非字符串键转换为其文本形式
+// - 字符 'c'、原始字符串 r#"..."# 和字节字符串 b"..." 都映射为字符串
+enum Parenthesized {
+    Unit,
+    Struct(Map<String, Value>),
+    Tuple(Vec<Value>),
+}
+
+struct Parser {
+    input: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Self {
+        Parser {
+            input: input.chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input.get(self.position).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.input.get(self.position + offset).copied()
+    }
+
+    fn error(&self, message: &str) -> anyhow::Error {
+        let consumed = &self.input[..self.position.min(self.input.len())];
+        let line = consumed.iter().filter(|&&c| c == '\n').count() + 1;
+        anyhow!("RON 解析错误: {} (第 {} 行)", message, line)
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        self.skip_whitespace()?;
+        if self.peek() != Some(c) {
+            return Err(self.error(&format!("期望 {}", c)));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) -> Result<()> {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => self.position += 1,
+                Some('/') if self.peek_at(1) == Some('/') => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.position += 1;
+                    }
+                }
+                Some('/') if self.peek_at(1) == Some('*') => {
+                    // RON 的块注释可以嵌套
+                    self.position += 2;
+                    let mut depth = 1;
+                    while depth > 0 {
+                        match (self.peek(), self.peek_at(1)) {
+                            (Some('/'), Some('*')) => {
+                                depth += 1;
+                                self.position += 2;
+                            }
+                            (Some('*'), Some('/')) => {
+                                depth -= 1;
+                                self.position += 2;
+                            }
+                            (Some(_), _) => self.position += 1,
+                            (None, _) => return Err(self.error("未闭合的注释")),
+                        }
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn parse(&mut self) -> Result<Value> {
+        self.skip_extensions()?;
+        let value = self.parse_value()?;
+        self.skip_whitespace()?;
+        if self.peek().is_some() {
+            return Err(self.error("解析未完成"));
+        }
+        Ok(value)
+    }
+
+    // 忽略文件开头的 #![enable(...)] 扩展声明
+    fn skip_extensions(&mut self) -> Result<()> {
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some('#') && self.peek_at(1) == Some('!') {
+                while self.peek().is_some_and(|c| c != ']') {
+                    self.position += 1;
+                }
+                self.position += 1;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value> {
+        self.skip_whitespace()?;
+        match self.peek() {
+            Some('(') => Ok(match self.parse_parenthesized()? {
+                Parenthesized::Unit => Value::Null,
+                Parenthesized::Struct(fields) => Value::Object(fields),
+                Parenthesized::Tuple(items) => Value::Array(items),
+            }),
+            Some('[') => self.parse_list(),
+            Some('{') => self.parse_map(),
+            Some('"') => Ok(Value::String(self.read_string()?)),
+            Some('\'') => Ok(Value::String(self.read_char()?.to_string())),
+            Some('r') if matches!(self.peek_at(1), Some('"') | Some('#')) => Ok(Value::String(self.read_raw_string()?)),
+            Some('b') if self.peek_at(1) == Some('"') => {
+                self.position += 1;
+                Ok(Value::String(self.read_string()?))
+            }
+            Some(c) if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => self.read_number(),
+            Some(c) if is_identifier_start(c) => self.parse_identifier_value(),
+            Some(c) => Err(self.error(&format!("无效的字符: {}", c))),
+            None => Err(self.error("意外的文件结尾")),
+        }
+    }
+
+    fn parse_identifier_value(&mut self) -> Result<Value> {
+        let name = self.read_identifier();
+        match name.as_str() {
+            "true" => return Ok(Value::Bool(true)),
+            "false" => return Ok(Value::Bool(false)),
+            "None" => return Ok(Value::Null),
+            "inf" | "NaN" => return Err(self.error(&format!("不支持的数值: {}", name))),
+            _ => {}
+        }
+
+        let start = self.position;
+        self.skip_whitespace()?;
+        if self.peek() != Some('(') {
+            self.position = start;
+            return Ok(Value::String(name));
+        }
+
+        if name == "Some" {
+            self.position += 1;
+            let value = self.parse_value()?;
+            self.skip_whitespace()?;
+            if self.peek() == Some(',') {
+                self.position += 1;
+            }
+            self.expect(')')?;
+            return Ok(value);
+        }
+
+        match self.parse_parenthesized()? {
+            Parenthesized::Unit => Ok(Value::String(name)),
+            // 具名结构体丢弃名字
+            Parenthesized::Struct(fields) => Ok(Value::Object(fields)),
+            Parenthesized::Tuple(mut items) => {
+                let content = if items.len() == 1 { items.remove(0) } else { Value::Array(items) };
+                let mut tagged = Map::new();
+                tagged.insert(name, content);
+                Ok(Value::Object(tagged))
+            }
+        }
+    }
+
+    fn parse_parenthesized(&mut self) -> Result<Parenthesized> {
+        self.position += 1; // 跳过 (
+        self.skip_whitespace()?;
+        if self.peek() == Some(')') {
+            self.position += 1;
+            return Ok(Parenthesized::Unit);
+        }
+
+        // 标识符后紧跟 : 说明是结构体字段，否则是元组
+        let start = self.position;
+        let is_struct = if self.peek().is_some_and(is_identifier_start) {
+            self.read_identifier();
+            self.skip_whitespace()?;
+            let result = self.peek() == Some(':') && self.peek_at(1) != Some(':');
+            self.position = start;
+            result
+        } else {
+            false
+        };
+
+        if is_struct {
+            let mut fields = Map::new();
+            loop {
+                self.skip_whitespace()?;
+                if self.peek() == Some(')') {
+                    self.position += 1;
+                    break;
+                }
+                let field = self.read_identifier();
+                if field.is_empty() {
+                    return Err(self.error("无效的字段名"));
+                }
+                self.expect(':')?;
+                let value = self.parse_value()?;
+                fields.insert(field, value);
+                if !self.separator(')')? {
+                    break;
+                }
+            }
+            return Ok(Parenthesized::Struct(fields));
+        }
+
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some(')') {
+                self.position += 1;
+                break;
+            }
+            items.push(self.parse_value()?);
+            if !self.separator(')')? {
+                break;
+            }
+        }
+        Ok(Parenthesized::Tuple(items))
+    }
+
+    // 处理元素之间的逗号；遇到结束符时消费它并返回 false
+    fn separator(&mut self, close: char) -> Result<bool> {
+        self.skip_whitespace()?;
+        match self.peek() {
+            Some(',') => {
+                self.position += 1;
+                Ok(true)
+            }
+            Some(c) if c == close => {
+                self.position += 1;
+                Ok(false)
+            }
+            _ => Err(self.error(&format!("期望 , 或 {}", close))),
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<Value> {
+        self.position += 1; // 跳过 [
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some(']') {
+                self.position += 1;
+                break;
+            }
+            items.push(self.parse_value()?);
+            if !self.separator(']')? {
+                break;
+            }
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn parse_map(&mut self) -> Result<Value> {
+        self.position += 1; // 跳过 {
+        let mut map = Map::new();
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some('}') {
+                self.position += 1;
+                break;
+            }
+            let key = match self.parse_value()? {
+                Value::String(s) => s,
+                other => other.to_string(),
+            };
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            map.insert(key, value);
+            if !self.separator('}')? {
+                break;
+            }
+        }
+        Ok(Value::Object(map))
+    }
+
+    fn read_identifier(&mut self) -> String {
+        let mut result = String::new();
+        // r#ident 形式的原始标识符
+        if self.peek() == Some('r') && self.peek_at(1) == Some('#') {
+            self.position += 2;
+        }
+        while let Some(c) = self.peek() {
+            if is_identifier_start(c) || c.is_ascii_digit() {
+                result.push(c);
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+        result
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        self.position += 1; // 跳过 "
+        let mut result = String::new();
+        loop {
+            let c = self.peek().ok_or_else(|| self.error("未闭合的字符串"))?;
+            self.position += 1;
+            match c {
+                '"' => return Ok(result),
+                '\\' => result.push(self.read_escape()?),
+                c => result.push(c),
+            }
+        }
+    }
+
+    fn read_char(&mut self) -> Result<char> {
+        self.position += 1; // 跳过 '
+        let c = self.peek().ok_or_else(|| self.error("未闭合的字符"))?;
+        self.position += 1;
+        let c = if c == '\\' { self.read_escape()? } else { c };
+        if self.peek() != Some('\'') {
+            return Err(self.error("未闭合的字符"));
+        }
+        self.position += 1;
+        Ok(c)
+    }
+
+    fn read_escape(&mut self) -> Result<char> {
+        let c = self.peek().ok_or_else(|| self.error("未闭合的字符串"))?;
+        self.position += 1;
+        Ok(match c {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            'x' => {
+                let hex: String = self.input.iter().skip(self.position).take(2).collect();
+                self.position += 2;
+                let code = u32::from_str_radix(&hex, 16).map_err(|_| self.error("无效的转义"))?;
+                char::from_u32(code).ok_or_else(|| self.error("无效的转义"))?
+            }
+            'u' => {
+                if self.peek() != Some('{') {
+                    return Err(self.error("无效的 Unicode 转义"));
+                }
+                self.position += 1;
+                let mut hex = String::new();
+                while let Some(c) = self.peek() {
+                    self.position += 1;
+                    if c == '}' {
+                        break;
+                    }
+                    hex.push(c);
+                }
+                let code = u32::from_str_radix(&hex, 16).map_err(|_| self.error("无效的 Unicode 转义"))?;
+                char::from_u32(code).ok_or_else(|| self.error("无效的 Unicode 转义"))?
+            }
+            other => other,
+        })
+    }
+
+    fn read_raw_string(&mut self) -> Result<String> {
+        self.position += 1; // 跳过 r
+        let mut hashes = 0;
+        while self.peek() == Some('#') {
+            hashes += 1;
+            self.position += 1;
+        }
+        if self.peek() != Some('"') {
+            return Err(self.error("无效的原始字符串"));
+        }
+        self.position += 1;
+
+        let mut result = String::new();
+        loop {
+            let c = self.peek().ok_or_else(|| self.error("未闭合的原始字符串"))?;
+            self.position += 1;
+            if c == '"' && (0..hashes).all(|i| self.peek_at(i) == Some('#')) {
+                self.position += hashes;
+                return Ok(result);
+            }
+            result.push(c);
+        }
+    }
+
+    fn read_number(&mut self) -> Result<Value> {
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | '_') {
+                // 指数部分之外的 +/- 不属于数字
+                if matches!(c, '-' | '+') && !text.is_empty() && !text.ends_with(['e', 'E']) {
+                    break;
+                }
+                if c != '_' {
+                    text.push(c);
+                }
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(&text)),
+        };
+        // 去掉 i32、u8、f64 等类型后缀
+        let digits = strip_type_suffix(digits);
+
+        for (prefix, radix) in [("0x", 16), ("0b", 2), ("0o", 8)] {
+            if let Some(body) = digits.strip_prefix(prefix) {
+                let n = i64::from_str_radix(body, radix).map_err(|_| self.error(&format!("无效的数字: {}", text)))?;
+                return Ok(Value::Number(Number::from(if negative { -n } else { n })));
+            }
+        }
+
+        let signed = if negative { format!("-{}", digits) } else { digits.to_string() };
+        if let Ok(n) = signed.parse::<i64>() {
+            return Ok(Value::Number(Number::from(n)));
+        }
+        if let Ok(n) = signed.parse::<u64>() {
+            return Ok(Value::Number(Number::from(n)));
+        }
+        signed
+            .parse::<f64>()
+            .ok()
+            .filter(|f| f.is_finite())
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| self.error(&format!("无效的数字: {}", text)))
+    }
+}
+
+fn strip_type_suffix(digits: &str) -> &str {
+    for suffix in ["i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32", "f64"] {
+        if let Some(rest) = digits.strip_suffix(suffix) {
+            if !rest.is_empty() && !rest.starts_with("0x") {
+                return rest;
+            }
+        }
+    }
+    digits
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+pub fn parse_ron(input: &str) -> Result<HashMap<String, Value>> {
+    let mut parser = Parser::new(input);
+    let value = parser.parse()?;
+
+    match value {
+        Value::Object(map) => Ok(map.into_iter().collect()),
+        _ => Err(anyhow!("RON 根节点必须是结构体或字典")),
+    }
+}
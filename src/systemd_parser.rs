@@ -0,0 +1,80 @@
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+// systemd 单元文件解析器。与 JSON 的映射规则：
+// - `[Section]` 映射为对象，同一节重复出现时合并
+// - 同一指令重复出现时合并为数组，`ExecStartPre=a` 再 `ExecStartPre=b` -> ["a", "b"]
+// - 空赋值 `ExecStartPre=` 清空之前的值，并以 "" 记录下来，使 drop-in 文件中的重置在往返转换后仍然有效
+// - 以反斜杠结尾的行与下一行拼接，反斜杠替换为空格；以 # 或 ; 开头的行是注释
+// - 值一律保留为字符串，不做类型推断
+pub fn parse_systemd(input: &str) -> Result<HashMap<String, Value>> {
+    let mut root = Map::new();
+    let mut section: Option<String> = None;
+    let mut lines = input.lines().enumerate();
+
+    while let Some((index, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            let name = line
+                .strip_prefix('[')
+                .and_then(|l| l.strip_suffix(']'))
+                .filter(|name| !name.is_empty())
+                .ok_or_else(|| anyhow!("第 {} 行: 无效的节头: {}", index + 1, line))?;
+            root.entry(name.to_string()).or_insert_with(|| Value::Object(Map::new()));
+            section = Some(name.to_string());
+            continue;
+        }
+
+        // 续行之间的注释行被跳过
+        let mut logical = line.to_string();
+        while logical.ends_with('\\') {
+            logical.pop();
+            logical.truncate(logical.trim_end().len());
+            logical.push(' ');
+            let next = lines.by_ref().map(|(_, l)| l).find(|l| !l.trim_start().starts_with(['#', ';']));
+            match next {
+                Some(next) => logical.push_str(next.trim()),
+                None => break,
+            }
+        }
+
+        let Some(name) = &section else {
+            return Err(anyhow!("第 {} 行: 指令必须位于节内", index + 1));
+        };
+        let (key, value) = logical
+            .split_once('=')
+            .ok_or_else(|| anyhow!("第 {} 行: 缺少 =: {}", index + 1, line))?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(anyhow!("第 {} 行: 指令名为空", index + 1));
+        }
+        let value = value.trim();
+
+        let target = root
+            .get_mut(name)
+            .and_then(Value::as_object_mut)
+            .ok_or_else(|| anyhow!("键冲突: {}", name))?;
+        match target.get_mut(key) {
+            _ if value.is_empty() => {
+                target.insert(key.to_string(), Value::String(String::new()));
+            }
+            Some(Value::Array(values)) => values.push(Value::String(value.to_string())),
+            Some(existing) => *existing = Value::Array(vec![existing.take(), Value::String(value.to_string())]),
+            None => {
+                target.insert(key.to_string(), Value::String(value.to_string()));
+            }
+        }
+    }
+
+    Ok(root.into_iter().collect())
+}
+
+// 指令名不能为空，不能包含 = 或换行，也不能被误认为节头或注释
+pub fn is_directive_name(name: &str) -> bool {
+    !name.is_empty() && name.trim() == name && !name.contains(['=', '\n']) && !name.starts_with(['[', '#', ';'])
+}
@@ -0,0 +1,205 @@
+use crate::config_path::{format_path, PathSegment};
+use crate::toml_writer::{to_item, to_value};
+use crate::ConfigEditor;
+use anyhow::{anyhow, bail, Result};
+use serde_json::Value;
+use toml_edit::{Array, ArrayOfTables, DocumentMut, InlineTable, Item, Table};
+
+impl ConfigEditor for crate::TomlConverter {
+    fn set(&self, content: &str, path: &[PathSegment], value: &Value) -> Result<String> {
+        let mut doc: DocumentMut = content.parse().map_err(|e| anyhow!("TOML 解析错误: {}", e))?;
+        let (last, parents) = path.split_last().ok_or_else(|| anyhow!("路径不能为空"))?;
+
+        let mut item = doc.as_item_mut();
+        for (i, segment) in parents.iter().enumerate() {
+            item = child_or_insert(item, segment, container_for(&path[i + 1..], value))?;
+        }
+
+        // 已存在的值保留原有的前后缀（注释、空白），只替换值本身
+        if let Some(old) = child_mut(item, last) {
+            let new = if old.is_table() || old.is_array_of_tables() {
+                to_item(value, &self.options)?
+            } else {
+                Item::Value(to_value(value, &self.options)?)
+            };
+            replace_item(old, new);
+            return Ok(doc.to_string());
+        }
+
+        match (item, last) {
+            (Item::Table(table), PathSegment::Key(key)) => {
+                table.insert(key, to_item(value, &self.options)?);
+            }
+            (Item::Value(toml_edit::Value::InlineTable(table)), PathSegment::Key(key)) => {
+                insert_inline(table, key, to_value(value, &self.options)?);
+            }
+            (Item::Value(toml_edit::Value::Array(array)), PathSegment::Index(index)) if *index == array.len() => {
+                array.push(to_value(value, &self.options)?);
+            }
+            (Item::ArrayOfTables(tables), PathSegment::Index(index)) if *index == tables.len() => {
+                match to_item(value, &self.options)? {
+                    Item::Table(table) => tables.push(table),
+                    _ => bail!("表数组中只能追加表: {}", format_path(path)),
+                }
+            }
+            (Item::Value(toml_edit::Value::Array(array)), PathSegment::Index(index)) => {
+                bail!("无法设置 {}: 下标 {} 超出数组长度 {}", format_path(path), index, array.len())
+            }
+            (Item::ArrayOfTables(tables), PathSegment::Index(index)) => {
+                bail!("无法设置 {}: 下标 {} 超出数组长度 {}", format_path(path), index, tables.len())
+            }
+            _ => bail!("无法在 {} 处写入值", format_path(path)),
+        }
+
+        Ok(doc.to_string())
+    }
+
+    fn remove(&self, content: &str, path: &[PathSegment]) -> Result<String> {
+        let mut doc: DocumentMut = content.parse().map_err(|e| anyhow!("TOML 解析错误: {}", e))?;
+        let (last, parents) = path.split_last().ok_or_else(|| anyhow!("路径不能为空"))?;
+
+        let mut item = doc.as_item_mut();
+        for segment in parents {
+            item = child_mut(item, segment).ok_or_else(|| anyhow!("路径不存在: {}", format_path(path)))?;
+        }
+
+        let removed = match (item, last) {
+            (Item::Table(table), PathSegment::Key(key)) => table.remove(key).is_some(),
+            (Item::Value(toml_edit::Value::InlineTable(table)), PathSegment::Key(key)) => remove_inline(table, key),
+            (Item::Value(toml_edit::Value::Array(array)), PathSegment::Index(index)) if *index < array.len() => {
+                array.remove(*index);
+                // 删除第一个元素后，去掉新的第一个元素前原本跟在逗号后的空格
+                if *index == 0 {
+                    if let Some(first) = array.get_mut(0) {
+                        first.decor_mut().set_prefix("");
+                    }
+                }
+                true
+            }
+            (Item::ArrayOfTables(tables), PathSegment::Index(index)) if *index < tables.len() => {
+                tables.remove(*index);
+                true
+            }
+            _ => false,
+        };
+
+        if !removed {
+            bail!("路径不存在: {}", format_path(path));
+        }
+
+        Ok(doc.to_string())
+    }
+}
+
+// 缺失的中间层要创建的容器
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Container {
+    Table,
+    Array,
+    ArrayOfTables,
+}
+
+// 根据中间层之后的路径决定创建什么：后面是键时创建表；后面是下标时，元素是表（路径继续向下取键，或者写入的值是对象）则创建表数组，否则创建数组
+fn container_for(rest: &[PathSegment], value: &Value) -> Container {
+    match rest {
+        [PathSegment::Key(_), ..] => Container::Table,
+        [PathSegment::Index(_), PathSegment::Key(_), ..] => Container::ArrayOfTables,
+        [PathSegment::Index(_)] if value.is_object() => Container::ArrayOfTables,
+        _ => Container::Array,
+    }
+}
+
+// Item::get_mut 取不存在的键时会插入一个空的占位项，先用 get 确认键存在
+fn child_mut<'a>(item: &'a mut Item, segment: &PathSegment) -> Option<&'a mut Item> {
+    match segment {
+        PathSegment::Key(key) => {
+            item.get(key.as_str())?;
+            item.get_mut(key.as_str())
+        }
+        PathSegment::Index(index) => item.get_mut(*index),
+    }
+}
+
+fn child_or_insert<'a>(item: &'a mut Item, segment: &PathSegment, container: Container) -> Result<&'a mut Item> {
+    // 中间层缺失时自动创建：普通表下创建隐式表、数组或表数组，内联表和数组中创建内联表或数组；数组下标等于长度时追加元素
+    match (&mut *item, segment) {
+        (Item::Table(table), PathSegment::Key(key)) if !table.contains_key(key) => {
+            let child = match container {
+                Container::Table => {
+                    let mut child = Table::new();
+                    child.set_implicit(true);
+                    Item::Table(child)
+                }
+                Container::Array => Item::Value(toml_edit::Value::Array(Array::new())),
+                Container::ArrayOfTables => Item::ArrayOfTables(ArrayOfTables::new()),
+            };
+            table.insert(key, child);
+        }
+        (Item::Value(toml_edit::Value::InlineTable(table)), PathSegment::Key(key)) if !table.contains_key(key) => {
+            insert_inline(table, key, inline_container(container));
+        }
+        (Item::Value(toml_edit::Value::Array(array)), PathSegment::Index(index)) if *index == array.len() => {
+            array.push(inline_container(container));
+        }
+        (Item::ArrayOfTables(tables), PathSegment::Index(index)) if *index == tables.len() => {
+            if container != Container::Table {
+                bail!("表数组的元素只能是表: {}", segment_name(segment));
+            }
+            tables.push(Table::new());
+        }
+        _ => {}
+    }
+
+    child_mut(item, segment).ok_or_else(|| anyhow!("无法进入路径: {}", segment_name(segment)))
+}
+
+// 内联表和数组中只能放内联的值，表数组的元素也写成内联表
+fn inline_container(container: Container) -> toml_edit::Value {
+    match container {
+        Container::Table => toml_edit::Value::InlineTable(InlineTable::new()),
+        Container::Array | Container::ArrayOfTables => toml_edit::Value::Array(Array::new()),
+    }
+}
+
+// 原来最后一个值后面的空白在 } 之前，追加新键前去掉，否则会写成 { enabled = true , cert = "c.pem" }
+fn insert_inline(table: &mut InlineTable, key: &str, value: toml_edit::Value) {
+    if let Some((_, last)) = table.iter_mut().last() {
+        last.decor_mut().set_suffix("");
+    }
+    table.insert(key, value);
+}
+
+// 删除最后一个键时，把它 } 之前的空白交给新的最后一个值
+fn remove_inline(table: &mut InlineTable, key: &str) -> bool {
+    let is_last = table.iter().last().is_some_and(|(last, _)| last == key);
+    let Some(removed) = table.remove(key) else {
+        return false;
+    };
+    if is_last {
+        if let (Some((_, last)), Some(suffix)) = (table.iter_mut().last(), removed.decor().suffix()) {
+            last.decor_mut().set_suffix(suffix.clone());
+        }
+    }
+    true
+}
+
+fn segment_name(segment: &PathSegment) -> String {
+    format_path(std::slice::from_ref(segment))
+}
+
+fn replace_item(old: &mut Item, new: Item) {
+    match (old, new) {
+        (Item::Value(old), Item::Value(mut new)) => {
+            *new.decor_mut() = old.decor().clone();
+            *old = new;
+        }
+        (Item::Table(old), Item::Table(mut new)) => {
+            *new.decor_mut() = old.decor().clone();
+            if let Some(position) = old.position() {
+                new.set_position(position);
+            }
+            *old = new;
+        }
+        (old, new) => *old = new,
+    }
+}
@@ -0,0 +1,109 @@
+use crate::TomlOptions;
+use anyhow::{anyhow, bail, Result};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use toml_edit::{Array, ArrayOfTables, DocumentMut, InlineTable, Item, Table};
+
+pub(crate) fn to_document(value: &HashMap<String, Value>, options: &TomlOptions) -> Result<DocumentMut> {
+    let mut doc = DocumentMut::new();
+    let mut keys: Vec<_> = value.keys().collect();
+    keys.sort();
+    for key in keys {
+        doc.insert(key, to_item(&value[key], options)?);
+    }
+    Ok(doc)
+}
+
+// 转换为标准表上下文中的条目；toml_edit 在输出时总会把键值对排在子表之前，因此不会出现
+// "values must be emitted before tables" 的问题
+pub(crate) fn to_item(value: &Value, options: &TomlOptions) -> Result<Item> {
+    match value {
+        Value::Object(obj) => {
+            if options.inline_tables {
+                let inline = to_value(value, options)?;
+                if fits(&inline, options) {
+                    return Ok(Item::Value(inline));
+                }
+            }
+            Ok(Item::Table(to_table(obj, options)?))
+        }
+        Value::Array(arr) if options.array_of_tables && !arr.is_empty() && arr.iter().all(Value::is_object) => {
+            let mut tables = ArrayOfTables::new();
+            for v in arr {
+                if let Value::Object(obj) = v {
+                    tables.push(to_table(obj, options)?);
+                }
+            }
+            Ok(Item::ArrayOfTables(tables))
+        }
+        _ => Ok(Item::Value(to_value(value, options)?)),
+    }
+}
+
+pub(crate) fn to_value(value: &Value, options: &TomlOptions) -> Result<toml_edit::Value> {
+    Ok(match value {
+        Value::Null => bail!("TOML 不支持 null 值"),
+        Value::Bool(b) => (*b).into(),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into()
+            } else if n.is_u64() {
+                bail!("数值超出 TOML 整数范围: {}", n)
+            } else {
+                n.as_f64().ok_or_else(|| anyhow!("无效的数字: {}", n))?.into()
+            }
+        }
+        Value::String(s) => s.as_str().into(),
+        Value::Array(arr) => {
+            let mut array = Array::new();
+            for v in arr {
+                array.push(to_value(v, options)?);
+            }
+            let mut value = toml_edit::Value::Array(array);
+            if !fits(&value, options) {
+                if let toml_edit::Value::Array(array) = &mut value {
+                    expand_array(array);
+                }
+            }
+            value
+        }
+        Value::Object(obj) => {
+            let mut table = InlineTable::new();
+            for (k, v) in sorted(obj) {
+                table.insert(k, to_value(v, options)?);
+            }
+            toml_edit::Value::InlineTable(table)
+        }
+    })
+}
+
+fn to_table(obj: &Map<String, Value>, options: &TomlOptions) -> Result<Table> {
+    let mut table = Table::new();
+    for (k, v) in sorted(obj) {
+        table.insert(k, to_item(v, options)?);
+    }
+    Ok(table)
+}
+
+fn sorted(obj: &Map<String, Value>) -> Vec<(&String, &Value)> {
+    let mut entries: Vec<_> = obj.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+fn fits(value: &toml_edit::Value, options: &TomlOptions) -> bool {
+    match options.max_inline_width {
+        Some(width) => value.to_string().trim().len() <= width,
+        None => true,
+    }
+}
+
+// 超出宽度的数组每个元素单独一行
+fn expand_array(array: &mut Array) {
+    for item in array.iter_mut() {
+        item.decor_mut().set_prefix("\n    ");
+        item.decor_mut().set_suffix("");
+    }
+    array.set_trailing_comma(true);
+    array.set_trailing("\n");
+}